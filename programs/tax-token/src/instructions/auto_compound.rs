@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{AutoCompoundUpdated, CompoundPreference};
+
+#[derive(Accounts)]
+pub struct SetAutoCompound<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = CompoundPreference::LEN,
+        seeds = [b"compound", owner.key().as_ref()],
+        bump
+    )]
+    pub preference: Account<'info, CompoundPreference>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// holders that opt in are paid their rewards in the tax token by `distribute_compound`
+// instead of the reward mint by `distribute`
+pub fn process_set_auto_compound(ctx: Context<SetAutoCompound>, enabled: bool) -> Result<()> {
+    let preference = &mut ctx.accounts.preference;
+    preference.owner = ctx.accounts.owner.key();
+    preference.enabled = enabled;
+    preference.bump = ctx.bumps.preference;
+
+    emit!(AutoCompoundUpdated {
+        owner: preference.owner,
+        enabled,
+    });
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, Token2022, TokenAccount};
+
+use crate::{add_to_total, ErrorCode, ProgramState, Role, Stats, Treasury};
+
+#[derive(Accounts)]
+pub struct BurnTax<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::TreasuryAdmin, authority.key) @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Account<'info, Stats>,
+
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = treasury.vault @ ErrorCode::InvalidTreasuryAccount)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// withdrawn tax can be burned from the treasury vault instead of being swapped for rewards
+// burning can't move funds anywhere, so it doesn't count against the treasury spend limit
+pub fn process_burn_tax(ctx: Context<BurnTax>, amount: u64) -> Result<()> {
+    let signer_seeds = ctx.accounts.treasury.signer_seeds();
+
+    burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint_account.to_account_info(),
+                from: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.treasury.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        amount,
+    )?;
+
+    add_to_total(&mut ctx.accounts.stats.total_tax_burned, amount)?;
+    Ok(())
+}
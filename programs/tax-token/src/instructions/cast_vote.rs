@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    add_to_total, ErrorCode, Proposal, ProposalStatus, StakePosition, VoteCast, VoteRecord,
+};
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        constraint = proposal.status == ProposalStatus::Voting @ ErrorCode::ProposalFinalized
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::LEN,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote: Account<'info, VoteRecord>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// votes are weighted by the voter's staked tokens; remaining accounts are their stake positions
+// only positions locked until the end of the vote count, so the same tokens can't vote twice
+pub fn process_cast_vote<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CastVote<'info>>,
+    support: bool,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    require!(
+        Clock::get()?.unix_timestamp < proposal.voting_ends_ts,
+        ErrorCode::VotingClosed
+    );
+
+    let voter = ctx.accounts.voter.key();
+    let mut weight: u64 = 0;
+    let mut counted: Vec<Pubkey> = Vec::new();
+    for account in ctx.remaining_accounts {
+        let position = Account::<StakePosition>::try_from(account)?;
+        let (position_key, _) = Pubkey::find_program_address(
+            &[b"stake", voter.as_ref(), &[position.term as u8]],
+            ctx.program_id,
+        );
+        require_keys_eq!(account.key(), position_key, ErrorCode::InvalidBatchData);
+        require!(
+            !counted.contains(&position_key),
+            ErrorCode::InvalidBatchData
+        );
+        counted.push(position_key);
+
+        if position.unlock_ts >= proposal.voting_ends_ts {
+            add_to_total(&mut weight, position.amount)?;
+        }
+    }
+    require!(weight > 0, ErrorCode::NothingStaked);
+
+    if support {
+        add_to_total(&mut proposal.yes_votes, weight)?;
+    } else {
+        add_to_total(&mut proposal.no_votes, weight)?;
+    }
+
+    let vote = &mut ctx.accounts.vote;
+    vote.proposal = proposal.key();
+    vote.voter = voter;
+    vote.support = support;
+    vote.weight = weight;
+    vote.bump = ctx.bumps.vote;
+
+    emit!(VoteCast {
+        id: proposal.id,
+        voter,
+        support,
+        weight,
+    });
+    Ok(())
+}
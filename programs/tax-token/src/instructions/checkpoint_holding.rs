@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+use crate::{Eligibility, HoldingRecord, ProgramState};
+
+#[derive(Accounts)]
+pub struct CheckpointHolding<'info> {
+    #[account(seeds = [b"program_state"], bump = state.bump)]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(seeds = [b"eligibility"], bump = eligibility.bump)]
+    pub eligibility: Account<'info, Eligibility>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = HoldingRecord::LEN,
+        seeds = [b"holding", owner.key().as_ref()],
+        bump
+    )]
+    pub holding: Account<'info, HoldingRecord>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: the holder's wallet; only used to derive their token account and holding record
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        associated_token::mint = mint_account,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub holder_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+// starts the holding clock for a holder, or restarts it when their balance is below the minimum
+// or the previous checkpoint is more than `MAX_CHECKPOINT_GAP` old, since they may have sold in between
+// anyone can call it, and the keeper checkpoints every holder on each run to keep their clocks going
+pub fn process_checkpoint_holding(ctx: Context<CheckpointHolding>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let min_balance = ctx.accounts.eligibility.min_balance;
+    let balance = ctx.accounts.holder_token_account.amount;
+
+    let holding = &mut ctx.accounts.holding;
+    if holding.since == 0 || balance == 0 || balance < min_balance || !holding.is_continuous(now) {
+        holding.since = now;
+    }
+    holding.checked_at = now;
+    holding.owner = ctx.accounts.owner.key();
+    holding.bump = ctx.bumps.holding;
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked_with_fee, Mint, Token2022, TokenAccount, TransferCheckedWithFee,
+};
+
+use super::get_transfer_fee_config;
+use crate::{ErrorCode, ProgramState, ReferralClaimed, ReferralConfig, Referrer};
+
+#[derive(Accounts)]
+pub struct ClaimReferral<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"referral_config"], bump = referral_config.bump)]
+    pub referral_config: Account<'info, ReferralConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", wallet.key().as_ref()],
+        bump = referrer.bump,
+        has_one = wallet
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    pub wallet: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = referral_config.vault)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// pays out as much of the referrer's claimable balance as the referral vault holds
+// the transfer fee applies, so the destination receives (amount - fee)
+pub fn process_claim_referral(ctx: Context<ClaimReferral>) -> Result<()> {
+    let referrer = &mut ctx.accounts.referrer;
+    let amount = referrer.claimable.min(ctx.accounts.vault.amount);
+    if amount == 0 {
+        return Ok(());
+    }
+    referrer.claimable -= amount;
+
+    let referral_config = &mut ctx.accounts.referral_config;
+    referral_config.total_owed = referral_config.total_owed.saturating_sub(amount);
+
+    // calculate expected fee
+    let epoch = Clock::get()?.epoch;
+    let fee = get_transfer_fee_config(&ctx.accounts.mint_account.to_account_info())?
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or(ErrorCode::FeeCalculationFailed)?;
+
+    let signer_seeds = referral_config.signer_seeds();
+
+    transfer_checked_with_fee(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferCheckedWithFee {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                source: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint_account.to_account_info(),
+                destination: ctx.accounts.destination.to_account_info(),
+                authority: referral_config.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        amount,                             // transfer amount
+        ctx.accounts.mint_account.decimals, // decimals
+        fee,                                // fee
+    )?;
+
+    emit!(ReferralClaimed {
+        referrer: referrer.wallet,
+        amount,
+        claimable: referrer.claimable,
+    });
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{ErrorCode, ProgramState, StakePool, StakePosition, StakeTerm};
+
+#[derive(Accounts)]
+#[instruction(term: StakeTerm)]
+pub struct ClaimStakeRewards<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"stake_pool"], bump = stake_pool.bump)]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", owner.key().as_ref(), &[term as u8]],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    pub owner: Signer<'info>,
+
+    #[account(address = state.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    /// The reward vault, owned by the program state PDA
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = state,
+        associated_token::token_program = token_program
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub owner_reward_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// stakers claim their share of the rewards set aside by `distribute_to_stakers`
+pub fn process_claim_stake_rewards(
+    ctx: Context<ClaimStakeRewards>,
+    _term: StakeTerm,
+) -> Result<()> {
+    let stake_pool = &mut ctx.accounts.stake_pool;
+    let position = &mut ctx.accounts.position;
+    position.accrue(stake_pool.acc_reward_per_weight)?;
+
+    let amount = position.pending_rewards;
+    if amount == 0 {
+        return Ok(());
+    }
+    position.pending_rewards = 0;
+    stake_pool.unclaimed_rewards = stake_pool.unclaimed_rewards.saturating_sub(amount);
+
+    let signer_seeds = ctx.accounts.state.signer_seeds();
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.owner_reward_account.to_account_info(),
+                authority: ctx.accounts.state.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+    Ok(())
+}
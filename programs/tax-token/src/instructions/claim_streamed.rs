@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    ErrorCode, ProgramState, RewardRecipient, RewardStream, StreamPool, StreamedRewardsClaimed,
+};
+
+#[derive(Accounts)]
+pub struct ClaimStreamed<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(seeds = [b"stream_pool"], bump = stream_pool.bump)]
+    pub stream_pool: Account<'info, StreamPool>,
+
+    #[account(
+        mut,
+        seeds = [b"stream", owner.key().as_ref()],
+        bump = stream.bump,
+        has_one = owner
+    )]
+    pub stream: Account<'info, RewardStream>,
+
+    /// CHECK: the stream's owner, who doesn't need to sign since the rewards only go to their recipient
+    pub owner: UncheckedAccount<'info>,
+    /// CHECK: the owner's reward recipient PDA, which may not be initialized
+    #[account(seeds = [b"reward_recipient", owner.key().as_ref()], bump)]
+    pub reward_recipient: UncheckedAccount<'info>,
+
+    #[account(address = state.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = stream_pool.vault)]
+    pub stream_vault: InterfaceAccount<'info, TokenAccount>,
+    /// A reward token account of the owner, or of their reward recipient if they set one
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::token_program = token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// anyone can claim whatever has unlocked from a holder's reward stream so far
+// like `distribute`, it pays the holder's reward recipient when they set one, so cold wallets and
+// contract-owned holders still receive their streamed rewards
+pub fn process_claim_streamed(ctx: Context<ClaimStreamed>) -> Result<()> {
+    let reward_recipient = &ctx.accounts.reward_recipient;
+    let recipient = if reward_recipient.data_is_empty() {
+        ctx.accounts.owner.key()
+    } else {
+        RewardRecipient::try_deserialize(&mut &reward_recipient.data.borrow()[..])?.recipient
+    };
+    require_keys_eq!(
+        ctx.accounts.destination.owner,
+        recipient,
+        ErrorCode::InvalidBatchData
+    );
+
+    let stream = &mut ctx.accounts.stream;
+    stream.settle(Clock::get()?.unix_timestamp)?;
+
+    let amount = stream.unlocked;
+    if amount == 0 {
+        return Ok(());
+    }
+    stream.unlocked = 0;
+
+    let signer_seeds = ctx.accounts.stream_pool.signer_seeds();
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.stream_vault.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.stream_pool.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+
+    emit!(StreamedRewardsClaimed {
+        owner: stream.owner,
+        amount,
+        locked: stream.locked,
+    });
+    Ok(())
+}
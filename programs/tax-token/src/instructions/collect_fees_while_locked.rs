@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke_signed};
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{ErrorCode, LockedFeesCollected, PositionLock, Treasury, RAYDIUM_CLMM_PROGRAM_IDS};
+
+// anchor discriminator of the CLMM program's `decrease_liquidity_v2` instruction
+const DECREASE_LIQUIDITY_V2: [u8; 8] = [58, 127, 188, 62, 79, 82, 196, 96];
+
+#[derive(Accounts)]
+pub struct CollectFeesWhileLocked<'info> {
+    #[account(
+        seeds = [b"position_lock", lock.position_mint.as_ref()],
+        bump = lock.bump,
+        has_one = escrow
+    )]
+    pub lock: Account<'info, PositionLock>,
+
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// CHECK: the escrow holding the position NFT, checked against the lock
+    pub escrow: UncheckedAccount<'info>,
+    /// CHECK: the CLMM personal position, validated by the CLMM program against the NFT
+    #[account(mut)]
+    pub personal_position: UncheckedAccount<'info>,
+    /// CHECK: validated by the CLMM program
+    #[account(mut)]
+    pub pool_state: UncheckedAccount<'info>,
+    /// CHECK: validated by the CLMM program
+    #[account(mut)]
+    pub protocol_position: UncheckedAccount<'info>,
+    /// CHECK: validated by the CLMM program
+    #[account(mut)]
+    pub token_vault_0: UncheckedAccount<'info>,
+    /// CHECK: validated by the CLMM program
+    #[account(mut)]
+    pub token_vault_1: UncheckedAccount<'info>,
+    /// CHECK: validated by the CLMM program
+    #[account(mut)]
+    pub tick_array_lower: UncheckedAccount<'info>,
+    /// CHECK: validated by the CLMM program
+    #[account(mut)]
+    pub tick_array_upper: UncheckedAccount<'info>,
+    /// The treasury's token account for the pool's first token
+    #[account(mut, token::authority = treasury)]
+    pub recipient_token_account_0: InterfaceAccount<'info, TokenAccount>,
+    /// The treasury's token account for the pool's second token
+    #[account(mut, token::authority = treasury)]
+    pub recipient_token_account_1: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: validated by the CLMM program
+    pub token_program: UncheckedAccount<'info>,
+    /// CHECK: validated by the CLMM program
+    pub token_program_2022: UncheckedAccount<'info>,
+    /// CHECK: validated by the CLMM program
+    pub memo_program: UncheckedAccount<'info>,
+    /// CHECK: validated by the CLMM program
+    pub vault_0_mint: UncheckedAccount<'info>,
+    /// CHECK: validated by the CLMM program
+    pub vault_1_mint: UncheckedAccount<'info>,
+    /// CHECK: checked against the known CLMM program IDs
+    #[account(
+        executable,
+        constraint = RAYDIUM_CLMM_PROGRAM_IDS.contains(clmm_program.key) @ ErrorCode::InvalidClmmProgram
+    )]
+    pub clmm_program: UncheckedAccount<'info>,
+}
+
+// anyone can collect the trading fees of a locked position; they always go to the treasury
+// this removes zero liquidity from the position, which makes the CLMM program pay out its fees
+// remaining accounts are passed through to the CLMM program: the optional tick array bitmap
+// extension, then a (reward vault, recipient token account, reward mint) triple per farm reward,
+// whose recipients must be owned by the treasury as well
+pub fn process_collect_fees_while_locked<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CollectFeesWhileLocked<'info>>,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    check_reward_recipients(accounts, ctx.remaining_accounts)?;
+
+    let mut data = DECREASE_LIQUIDITY_V2.to_vec();
+    data.extend_from_slice(&0u128.to_le_bytes()); // liquidity
+    data.extend_from_slice(&0u64.to_le_bytes()); // amount_0_min
+    data.extend_from_slice(&0u64.to_le_bytes()); // amount_1_min
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.lock.key(), true), // nft_owner
+        AccountMeta::new_readonly(accounts.escrow.key(), false), // nft_account
+        AccountMeta::new(accounts.personal_position.key(), false),
+        AccountMeta::new(accounts.pool_state.key(), false),
+        AccountMeta::new(accounts.protocol_position.key(), false),
+        AccountMeta::new(accounts.token_vault_0.key(), false),
+        AccountMeta::new(accounts.token_vault_1.key(), false),
+        AccountMeta::new(accounts.tick_array_lower.key(), false),
+        AccountMeta::new(accounts.tick_array_upper.key(), false),
+        AccountMeta::new(accounts.recipient_token_account_0.key(), false),
+        AccountMeta::new(accounts.recipient_token_account_1.key(), false),
+        AccountMeta::new_readonly(accounts.token_program.key(), false),
+        AccountMeta::new_readonly(accounts.token_program_2022.key(), false),
+        AccountMeta::new_readonly(accounts.memo_program.key(), false),
+        AccountMeta::new_readonly(accounts.vault_0_mint.key(), false),
+        AccountMeta::new_readonly(accounts.vault_1_mint.key(), false),
+    ];
+    metas.extend(ctx.remaining_accounts.iter().map(|account| AccountMeta {
+        pubkey: account.key(),
+        is_signer: false,
+        is_writable: account.is_writable,
+    }));
+
+    let mut account_infos = vec![
+        accounts.lock.to_account_info(),
+        accounts.escrow.to_account_info(),
+        accounts.personal_position.to_account_info(),
+        accounts.pool_state.to_account_info(),
+        accounts.protocol_position.to_account_info(),
+        accounts.token_vault_0.to_account_info(),
+        accounts.token_vault_1.to_account_info(),
+        accounts.tick_array_lower.to_account_info(),
+        accounts.tick_array_upper.to_account_info(),
+        accounts.recipient_token_account_0.to_account_info(),
+        accounts.recipient_token_account_1.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.token_program_2022.to_account_info(),
+        accounts.memo_program.to_account_info(),
+        accounts.vault_0_mint.to_account_info(),
+        accounts.vault_1_mint.to_account_info(),
+    ];
+    account_infos.extend(ctx.remaining_accounts.iter().cloned());
+
+    let ix = Instruction {
+        program_id: accounts.clmm_program.key(),
+        accounts: metas,
+        data,
+    };
+
+    let balance_0 = accounts.recipient_token_account_0.amount;
+    let balance_1 = accounts.recipient_token_account_1.amount;
+    invoke_signed(&ix, &account_infos, &[&accounts.lock.signer_seeds()])?;
+
+    ctx.accounts.recipient_token_account_0.reload()?;
+    ctx.accounts.recipient_token_account_1.reload()?;
+
+    emit!(LockedFeesCollected {
+        position_mint: ctx.accounts.lock.position_mint,
+        amount_0: ctx.accounts.recipient_token_account_0.amount - balance_0,
+        amount_1: ctx.accounts.recipient_token_account_1.amount - balance_1,
+    });
+    Ok(())
+}
+
+fn check_reward_recipients<'info>(
+    accounts: &CollectFeesWhileLocked<'info>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let (bitmap_extension, _) = Pubkey::find_program_address(
+        &[
+            b"pool_tick_array_bitmap_extension",
+            accounts.pool_state.key().as_ref(),
+        ],
+        accounts.clmm_program.key,
+    );
+    let rewards = match remaining_accounts.first() {
+        Some(account) if account.key() == bitmap_extension => &remaining_accounts[1..],
+        _ => remaining_accounts,
+    };
+    require!(rewards.len() % 3 == 0, ErrorCode::InvalidRewardRecipient);
+
+    for reward in rewards.chunks_exact(3) {
+        let recipient = InterfaceAccount::<TokenAccount>::try_from(&reward[1])?;
+        require_keys_eq!(
+            recipient.owner,
+            accounts.treasury.key(),
+            ErrorCode::InvalidRewardRecipient
+        );
+    }
+    Ok(())
+}
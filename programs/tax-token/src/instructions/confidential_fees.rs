@@ -0,0 +1,289 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::Instruction,
+    program::{invoke, invoke_signed},
+};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        confidential_transfer::instruction::{
+            approve_account, inner_withdraw, ConfidentialTransferInstruction,
+        },
+        confidential_transfer_fee::instruction::{
+            harvest_withheld_tokens_to_mint, inner_withdraw_withheld_tokens_from_mint,
+        },
+    },
+    instruction::TokenInstruction,
+    proof::ProofLocation,
+    solana_zk_token_sdk::zk_token_elgamal::pod::AeCiphertext,
+};
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+use super::token_accounts_for_mint;
+use crate::{
+    ConfidentialAccountApproved, ConfidentialFeesRevealed, ConfidentialFeesWithdrawn, ErrorCode,
+    ProgramState, Role, Treasury,
+};
+
+#[derive(Accounts)]
+pub struct ConfigureConfidentialVault<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::TreasuryAdmin, treasury_admin.key) @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    pub treasury_admin: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, address = treasury.vault @ ErrorCode::InvalidTreasuryAccount)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the context state account holding the verified pubkey validity proof, checked by the token program
+    pub proof_context: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveConfidentialAccount<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::Compliance, officer.key) @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    pub officer: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct HarvestConfidentialFees<'info> {
+    #[account(seeds = [b"program_state"], bump = state.bump)]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawConfidentialFees<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::Keeper, authority.key)
+            || state.has_role(Role::TreasuryAdmin, authority.key) @ ErrorCode::UnauthorizedAccess,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, address = treasury.vault @ ErrorCode::InvalidTreasuryAccount)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the context state account holding the verified ciphertext equality proof, checked by the token program
+    pub proof_context: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct RevealConfidentialFees<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::TreasuryAdmin, treasury_admin.key) @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    pub treasury_admin: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, address = treasury.vault @ ErrorCode::InvalidTreasuryAccount)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the context state account holding the verified withdraw proof, checked by the token program
+    pub proof_context: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// confidential fees can only be withdrawn to a token account configured for confidential transfers
+// this configures the treasury vault, signed by the treasury PDA; the treasury admin holds the vault's ElGamal key
+pub fn process_configure_confidential_vault(
+    ctx: Context<ConfigureConfidentialVault>,
+    decryptable_zero_balance: [u8; 36],
+    maximum_pending_balance_credit_counter: u64,
+) -> Result<()> {
+    // the token-2022 `configure_account` builder takes an off-chain ciphertext type, so the data is encoded here
+    let mut data = TokenInstruction::ConfidentialTransferExtension.pack();
+    data.push(ConfidentialTransferInstruction::ConfigureAccount.into());
+    data.extend_from_slice(&decryptable_zero_balance);
+    data.extend_from_slice(&maximum_pending_balance_credit_counter.to_le_bytes());
+    data.push(0); // proof instruction offset, 0 to use the context state account
+
+    let ix = Instruction {
+        program_id: ctx.accounts.token_program.key(),
+        accounts: vec![
+            AccountMeta::new(ctx.accounts.vault.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.mint_account.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.proof_context.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.treasury.key(), true),
+        ],
+        data,
+    };
+
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.mint_account.to_account_info(),
+            ctx.accounts.proof_context.to_account_info(),
+            ctx.accounts.treasury.to_account_info(),
+        ],
+        &[&ctx.accounts.treasury.signer_seeds()],
+    )?;
+    Ok(())
+}
+
+// when the mint doesn't auto-approve new accounts, every account configured for confidential
+// transfers (the treasury vault included) must be approved by the compliance officer before use;
+// the program state PDA is the confidential transfer authority
+pub fn process_approve_confidential_account(
+    ctx: Context<ApproveConfidentialAccount>,
+) -> Result<()> {
+    let ix = approve_account(
+        &ctx.accounts.token_program.key(),
+        &ctx.accounts.token_account.key(),
+        &ctx.accounts.mint_account.key(),
+        &ctx.accounts.state.key(),
+        &[],
+    )?;
+
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.token_account.to_account_info(),
+            ctx.accounts.mint_account.to_account_info(),
+            ctx.accounts.state.to_account_info(),
+        ],
+        &[&ctx.accounts.state.signer_seeds()],
+    )?;
+
+    emit!(ConfidentialAccountApproved {
+        officer: ctx.accounts.officer.key(),
+        token_account: ctx.accounts.token_account.key(),
+        owner: ctx.accounts.token_account.owner,
+    });
+    Ok(())
+}
+
+// same as `harvest`, for the fees withheld from confidential transfers
+// the harvested amounts stay encrypted, so they are not added to the lifetime totals
+pub fn process_harvest_confidential_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, HarvestConfidentialFees<'info>>,
+) -> Result<()> {
+    let sources = token_accounts_for_mint(ctx.remaining_accounts, &ctx.accounts.mint_account.key());
+    let source_keys = sources.iter().map(|source| source.key).collect::<Vec<_>>();
+
+    let ix = harvest_withheld_tokens_to_mint(
+        &ctx.accounts.token_program.key(),
+        &ctx.accounts.mint_account.key(),
+        &source_keys,
+    )?;
+
+    let mut account_infos = vec![ctx.accounts.mint_account.to_account_info()];
+    account_infos.extend(sources.iter().cloned());
+    invoke(&ix, &account_infos)?;
+    Ok(())
+}
+
+// confidential fees harvested to the mint are withdrawn into the treasury vault's confidential balance
+// the program state PDA is the withdraw authority; the equality proof is generated off-chain with the
+// withdraw withheld authority's ElGamal secret key and verified into `proof_context` beforehand
+pub fn process_withdraw_confidential_fees(
+    ctx: Context<WithdrawConfidentialFees>,
+    new_decryptable_available_balance: [u8; 36],
+) -> Result<()> {
+    let ix = inner_withdraw_withheld_tokens_from_mint(
+        &ctx.accounts.token_program.key(),
+        &ctx.accounts.mint_account.key(),
+        &ctx.accounts.vault.key(),
+        &AeCiphertext(new_decryptable_available_balance),
+        &ctx.accounts.state.key(),
+        &[],
+        ProofLocation::ContextStateAccount(&ctx.accounts.proof_context.key()),
+    )?;
+
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.mint_account.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.proof_context.to_account_info(),
+            ctx.accounts.state.to_account_info(),
+        ],
+        &[&ctx.accounts.state.signer_seeds()],
+    )?;
+
+    emit!(ConfidentialFeesWithdrawn {
+        authority: ctx.accounts.authority.key(),
+        destination: ctx.accounts.vault.key(),
+    });
+    Ok(())
+}
+
+// moves `amount` from the treasury vault's confidential balance to its public balance,
+// where `treasury_spend`, `burn_tax` and distributions can use it like any other withdrawn tax
+// the proof only shows the vault holds `amount`, not that it all came from fees (anyone can make a
+// confidential transfer to the vault), so it isn't added to the lifetime totals
+pub fn process_reveal_confidential_fees(
+    ctx: Context<RevealConfidentialFees>,
+    amount: u64,
+    new_decryptable_available_balance: [u8; 36],
+) -> Result<()> {
+    let ix = inner_withdraw(
+        &ctx.accounts.token_program.key(),
+        &ctx.accounts.vault.key(),
+        &ctx.accounts.mint_account.key(),
+        amount,
+        ctx.accounts.mint_account.decimals,
+        AeCiphertext(new_decryptable_available_balance),
+        &ctx.accounts.treasury.key(),
+        &[],
+        ProofLocation::ContextStateAccount(&ctx.accounts.proof_context.key()),
+    )?;
+
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.mint_account.to_account_info(),
+            ctx.accounts.proof_context.to_account_info(),
+            ctx.accounts.treasury.to_account_info(),
+        ],
+        &[&ctx.accounts.treasury.signer_seeds()],
+    )?;
+
+    emit!(ConfidentialFeesRevealed {
+        treasury_admin: ctx.accounts.treasury_admin.key(),
+        amount,
+    });
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked_with_fee, Mint, Token2022, TokenAccount, TransferCheckedWithFee,
+};
+
+use super::get_transfer_fee_config;
+use crate::{
+    ErrorCode, Governance, ProgramState, Proposal, ProposalAction, ProposalCreated, ProposalStatus,
+};
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(seeds = [b"program_state"], bump = state.bump)]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::LEN,
+        seeds = [b"proposal", governance.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::authority = proposer,
+        token::token_program = token_program
+    )]
+    pub proposer_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = governance.vault)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+// the proposer locks the proposal deposit in the governance vault until the proposal is finalized
+// the transfer fee applies, so the proposal records the amount received by the vault
+pub fn process_create_proposal(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+    action.validate()?;
+
+    let amount = ctx.accounts.governance.proposal_deposit;
+
+    // calculate expected fee
+    let epoch = Clock::get()?.epoch;
+    let fee = get_transfer_fee_config(&ctx.accounts.mint_account.to_account_info())?
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or(ErrorCode::FeeCalculationFailed)?;
+
+    if amount > 0 {
+        transfer_checked_with_fee(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferCheckedWithFee {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    source: ctx.accounts.proposer_token_account.to_account_info(),
+                    mint: ctx.accounts.mint_account.to_account_info(),
+                    destination: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.proposer.to_account_info(),
+                },
+            ),
+            amount,                             // transfer amount
+            ctx.accounts.mint_account.decimals, // decimals
+            fee,                                // fee
+        )?;
+    }
+
+    let governance = &mut ctx.accounts.governance;
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.id = governance.proposal_count;
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.action = action;
+    proposal.deposit = amount - fee;
+    proposal.yes_votes = 0;
+    proposal.no_votes = 0;
+    proposal.voting_ends_ts = Clock::get()?.unix_timestamp + governance.voting_period;
+    proposal.status = ProposalStatus::Voting;
+    proposal.bump = ctx.bumps.proposal;
+    governance.proposal_count += 1;
+
+    emit!(ProposalCreated {
+        id: proposal.id,
+        proposer: proposal.proposer,
+        action: proposal.action.clone(),
+        deposit: proposal.deposit,
+        voting_ends_ts: proposal.voting_ends_ts,
+    });
+    Ok(())
+}
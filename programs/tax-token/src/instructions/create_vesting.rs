@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        transfer_checked_with_fee, Mint, Token2022, TokenAccount, TransferCheckedWithFee,
+    },
+};
+
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::{
+    TransferFeeConfig, MAX_FEE_BASIS_POINTS,
+};
+
+use super::{get_transfer_fee_config, gross_up};
+use crate::{ErrorCode, ProgramState, Vesting, VestingCreated, VestingParams};
+
+#[derive(Accounts)]
+#[instruction(params: VestingParams)]
+pub struct CreateVesting<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Vesting::LEN,
+        seeds = [
+            b"vesting",
+            params.beneficiary.as_ref(),
+            params.schedule_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::authority = authority,
+        token::token_program = token_program
+    )]
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// The vesting vault, owned by the vesting PDA
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint_account,
+        associated_token::authority = vesting,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// locks `amount` for the beneficiary, vesting linearly from start to end after the cliff
+// a beneficiary can have any number of schedules, told apart by `schedule_id`
+// the vault is funded so that releasing everything nets the beneficiary `amount` at the current
+// fee rate, however it is split into releases, and the deposit is grossed up for the fee as well
+pub fn process_create_vesting(ctx: Context<CreateVesting>, params: VestingParams) -> Result<()> {
+    require!(
+        params.amount > 0
+            && params.start_ts <= params.cliff_ts
+            && params.cliff_ts <= params.end_ts
+            && params.start_ts < params.end_ts,
+        ErrorCode::InvalidVestingSchedule
+    );
+
+    let fee_config = get_transfer_fee_config(&ctx.accounts.mint_account.to_account_info())?;
+    let epoch = Clock::get()?.epoch;
+    let vault_amount = vesting_reserve(&fee_config, epoch, params.amount)?;
+    let (amount, fee) = gross_up(&fee_config, epoch, vault_amount)?;
+
+    transfer_checked_with_fee(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferCheckedWithFee {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                source: ctx.accounts.authority_token_account.to_account_info(),
+                mint: ctx.accounts.mint_account.to_account_info(),
+                destination: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        ),
+        amount,                             // transfer amount
+        ctx.accounts.mint_account.decimals, // decimals
+        fee,                                // fee
+    )?;
+
+    let vesting = &mut ctx.accounts.vesting;
+    vesting.beneficiary = params.beneficiary;
+    vesting.schedule_id = params.schedule_id;
+    vesting.vault = ctx.accounts.vault.key();
+    vesting.total_amount = params.amount;
+    vesting.released = 0;
+    vesting.start_ts = params.start_ts;
+    vesting.cliff_ts = params.cliff_ts;
+    vesting.end_ts = params.end_ts;
+    vesting.revocable = params.revocable;
+    vesting.revoked = false;
+    vesting.bump = ctx.bumps.vesting;
+
+    emit!(VestingCreated {
+        beneficiary: params.beneficiary,
+        schedule_id: params.schedule_id,
+        amount: params.amount,
+        start_ts: params.start_ts,
+        cliff_ts: params.cliff_ts,
+        end_ts: params.end_ts,
+        revocable: params.revocable,
+    });
+    Ok(())
+}
+
+// what the vault needs to pay out `net_amount` at the current fee rate
+// `maximum_fee` only caps the fee per release, so the reserve ignores it: many small releases
+// each pay the full rate on their part
+pub fn vesting_reserve(fee_config: &TransferFeeConfig, epoch: u64, net_amount: u64) -> Result<u64> {
+    let basis_points = u16::from(fee_config.get_epoch_fee(epoch).transfer_fee_basis_points) as u128;
+    let max_basis_points = MAX_FEE_BASIS_POINTS as u128;
+    require!(
+        basis_points < max_basis_points,
+        ErrorCode::FeeCalculationFailed
+    );
+    let amount = (net_amount as u128 * max_basis_points).div_ceil(max_basis_points - basis_points);
+    u64::try_from(amount).map_err(|_| ErrorCode::ArithmeticOverflow.into())
+}
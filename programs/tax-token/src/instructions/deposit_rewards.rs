@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{add_to_total, ProgramState, Stats};
+
+#[derive(Accounts)]
+pub struct DepositRewards<'info> {
+    #[account(seeds = [b"program_state"], bump = state.bump)]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Account<'info, Stats>,
+
+    pub depositor: Signer<'info>,
+
+    #[account(address = state.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = depositor,
+        token::token_program = token_program
+    )]
+    pub depositor_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// The reward vault, owned by the program state PDA
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = state,
+        associated_token::token_program = token_program
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// rewards (e.g. swapped tax) are deposited into the reward vault, from which `distribute` pays holders
+pub fn process_deposit_rewards(ctx: Context<DepositRewards>, amount: u64) -> Result<()> {
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.depositor_token_account.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.reward_vault.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+
+    add_to_total(&mut ctx.accounts.stats.total_rewards_deposited, amount)?;
+    Ok(())
+}
@@ -0,0 +1,231 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    add_to_total, CompoundPreference, DistributeEvent, Eligibility, ErrorCode, HoldingRecord,
+    ProgramState, RewardRecipient, Role, StakePool, Stats, MAX_TRANSFER_BATCH,
+};
+
+#[derive(Accounts)]
+pub struct Distribute<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::Keeper, keeper.key) @ ErrorCode::UnauthorizedAccess,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Account<'info, Stats>,
+
+    /// CHECK: the stake pool PDA, which may not be initialized yet; stakers' unclaimed rewards stay in the vault
+    #[account(seeds = [b"stake_pool"], bump)]
+    pub stake_pool: UncheckedAccount<'info>,
+
+    /// CHECK: the eligibility rules PDA, which may not be initialized yet
+    #[account(seeds = [b"eligibility"], bump)]
+    pub eligibility: UncheckedAccount<'info>,
+
+    pub keeper: Signer<'info>,
+
+    #[account(address = state.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    /// The reward vault, owned by the program state PDA
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = state,
+        associated_token::token_program = token_program
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// rewards are paid out from the reward vault by the keeper
+// remaining accounts are (holder token account, reward token account, reward recipient PDA,
+// compound preference PDA) groups, one per entry in `amounts`, followed by the holder's holding
+// record when a minimum holding age is set; every holder must pass the eligibility rules and
+// must not have opted into auto-compounding
+// rewards go to the holder's own reward token account, or to one owned by the recipient they set
+pub fn process_distribute<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    require!(!amounts.is_empty(), ErrorCode::EmptyTransferList);
+    require!(
+        amounts.len() <= MAX_TRANSFER_BATCH,
+        ErrorCode::BatchTooLarge
+    );
+
+    let rules = eligibility_rules(&ctx.accounts.eligibility)?;
+    let stride = rules.accounts_per_recipient(4);
+    require!(
+        ctx.remaining_accounts.len() == amounts.len() * stride,
+        ErrorCode::InvalidBatchData
+    );
+
+    let total_amount = amounts
+        .iter()
+        .try_fold(0u64, |total, amount| total.checked_add(*amount))
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let stake_pool = &ctx.accounts.stake_pool;
+    let reserved = if stake_pool.data_is_empty() {
+        0
+    } else {
+        StakePool::try_deserialize(&mut &stake_pool.data.borrow()[..])?.unclaimed_rewards
+    };
+    require!(
+        total_amount <= ctx.accounts.reward_vault.amount.saturating_sub(reserved),
+        ErrorCode::InsufficientRewards
+    );
+
+    let reward_mint = ctx.accounts.reward_mint.key();
+    let decimals = ctx.accounts.reward_mint.decimals;
+    let signer_seeds = ctx.accounts.state.signer_seeds();
+    let token_mint = ctx.accounts.state.token_mint;
+    let now = Clock::get()?.unix_timestamp;
+
+    for (accounts, amount) in ctx.remaining_accounts.chunks(stride).zip(amounts.iter()) {
+        let holder_account = eligible_holder(
+            &rules,
+            &accounts[0],
+            Some(&accounts[3]),
+            accounts.get(4),
+            &token_mint,
+            now,
+            ctx.program_id,
+        )?;
+        let destination = &accounts[1];
+        let destination_account = InterfaceAccount::<TokenAccount>::try_from(destination)?;
+        require_keys_eq!(
+            destination_account.mint,
+            reward_mint,
+            ErrorCode::InvalidBatchData
+        );
+
+        let (recipient_key, _) = Pubkey::find_program_address(
+            &[b"reward_recipient", holder_account.owner.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(
+            accounts[2].key(),
+            recipient_key,
+            ErrorCode::InvalidBatchData
+        );
+        let recipient = if accounts[2].data_is_empty() {
+            holder_account.owner
+        } else {
+            Account::<RewardRecipient>::try_from(&accounts[2])?.recipient
+        };
+        require_keys_eq!(
+            destination_account.owner,
+            recipient,
+            ErrorCode::InvalidBatchData
+        );
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.reward_vault.to_account_info(),
+                    mint: ctx.accounts.reward_mint.to_account_info(),
+                    to: destination.clone(),
+                    authority: ctx.accounts.state.to_account_info(),
+                },
+                &[&signer_seeds],
+            ),
+            *amount,
+            decimals,
+        )?;
+    }
+
+    let stats = &mut ctx.accounts.stats;
+    add_to_total(&mut stats.total_rewards_distributed, total_amount)?;
+    add_to_total(&mut stats.distribution_batches, 1)?;
+    stats.last_distribution_ts = now;
+
+    emit!(DistributeEvent {
+        keeper: ctx.accounts.keeper.key(),
+        reward_mint,
+        recipients: amounts.len() as u16,
+        total_amount,
+    });
+
+    Ok(())
+}
+
+// the eligibility rules, or the defaults (any positive balance) before they are initialized
+pub fn eligibility_rules(eligibility: &AccountInfo) -> Result<Eligibility> {
+    if eligibility.data_is_empty() {
+        Ok(Eligibility::default())
+    } else {
+        Eligibility::try_deserialize(&mut &eligibility.data.borrow()[..])
+    }
+}
+
+// loads a holder's tax token account and checks it against the eligibility rules
+// `holding` is the holder's holding record, required when a minimum holding age is set
+// `compound`, when given, must be the holder's compound preference PDA; holders who opted into
+// auto-compounding are rejected, since `distribute_compound` pays them instead
+pub fn eligible_holder<'info>(
+    rules: &Eligibility,
+    holder: &'info AccountInfo<'info>,
+    compound: Option<&'info AccountInfo<'info>>,
+    holding: Option<&'info AccountInfo<'info>>,
+    token_mint: &Pubkey,
+    now: i64,
+    program_id: &Pubkey,
+) -> Result<InterfaceAccount<'info, TokenAccount>> {
+    let holder_account = InterfaceAccount::<TokenAccount>::try_from(holder)?;
+
+    // the holder token account must be the holder's associated token account of the tax token
+    require_keys_eq!(
+        holder.key(),
+        get_associated_token_address_with_program_id(
+            &holder_account.owner,
+            token_mint,
+            holder.owner
+        ),
+        ErrorCode::InvalidBatchData
+    );
+    require!(
+        rules.is_eligible(&holder_account.owner, holder_account.amount),
+        ErrorCode::IneligibleRecipient
+    );
+
+    // staked tokens are rewarded through `distribute_to_stakers`, so the stake vault never is
+    let (stake_pool, _) = Pubkey::find_program_address(&[b"stake_pool"], program_id);
+    require_keys_neq!(
+        holder_account.owner,
+        stake_pool,
+        ErrorCode::IneligibleRecipient
+    );
+
+    if let Some(compound) = compound {
+        let (compound_key, _) =
+            Pubkey::find_program_address(&[b"compound", holder_account.owner.as_ref()], program_id);
+        require_keys_eq!(compound.key(), compound_key, ErrorCode::InvalidBatchData);
+        if !compound.data_is_empty() {
+            let preference = CompoundPreference::try_deserialize(&mut &compound.data.borrow()[..])?;
+            require!(!preference.enabled, ErrorCode::HolderCompounds);
+        }
+    }
+
+    if rules.min_holding_seconds > 0 {
+        let holding = holding.ok_or(ErrorCode::InvalidBatchData)?;
+        let (holding_key, _) =
+            Pubkey::find_program_address(&[b"holding", holder_account.owner.as_ref()], program_id);
+        require_keys_eq!(holding.key(), holding_key, ErrorCode::InvalidBatchData);
+        let holding = Account::<HoldingRecord>::try_from(holding)?;
+        require!(
+            holding.is_continuous(now)
+                && holding.since.saturating_add(rules.min_holding_seconds) <= now,
+            ErrorCode::IneligibleRecipient
+        );
+    }
+    Ok(holder_account)
+}
@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked_with_fee, Mint, Token2022, TokenAccount, TransferCheckedWithFee,
+};
+
+use super::{eligibility_rules, eligible_holder, get_transfer_fee_config};
+use crate::{
+    add_to_total, CompoundDistributed, CompoundPreference, CompoundTally, ErrorCode, ProgramState,
+    Role, Treasury, MAX_TRANSFER_BATCH,
+};
+
+#[derive(Accounts)]
+pub struct DistributeCompound<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::Keeper, keeper.key) @ ErrorCode::UnauthorizedAccess,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    /// CHECK: the eligibility rules PDA, which may not be initialized yet
+    #[account(seeds = [b"eligibility"], bump)]
+    pub eligibility: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [b"compound_tally"], bump = tally.bump)]
+    pub tally: Account<'info, CompoundTally>,
+
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    pub keeper: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = treasury.vault @ ErrorCode::InvalidTreasuryAccount)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// pays compounding holders in the tax token from the un-swapped tax in the treasury vault
+// remaining accounts are (holder token account, compound preference) pairs, one per entry in
+// `amounts`, followed by the holder's holding record when a minimum holding age is set;
+// every holder must have been tallied this round, and payouts count against the treasury spend limit
+pub fn process_distribute_compound<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeCompound<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    require!(!amounts.is_empty(), ErrorCode::EmptyTransferList);
+    require!(
+        amounts.len() <= MAX_TRANSFER_BATCH,
+        ErrorCode::BatchTooLarge
+    );
+
+    let rules = eligibility_rules(&ctx.accounts.eligibility)?;
+    let stride = rules.accounts_per_recipient(2);
+    require!(
+        ctx.remaining_accounts.len() == amounts.len() * stride,
+        ErrorCode::InvalidBatchData
+    );
+
+    let round = ctx.accounts.tally.round;
+    require!(round > 0, ErrorCode::NotCompounding);
+
+    let total_amount = amounts
+        .iter()
+        .try_fold(0u64, |total, amount| total.checked_add(*amount))
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.treasury.record_spend(total_amount, now)?;
+
+    let mint = &ctx.accounts.mint_account.to_account_info();
+    let token_mint = ctx.accounts.state.token_mint;
+    let decimals = ctx.accounts.mint_account.decimals;
+    let epoch = Clock::get()?.epoch;
+    let treasury = &ctx.accounts.treasury;
+    let signer_seeds = treasury.signer_seeds();
+
+    for (accounts, amount) in ctx.remaining_accounts.chunks(stride).zip(amounts.iter()) {
+        let holder_account = eligible_holder(
+            &rules,
+            &accounts[0],
+            None,
+            accounts.get(2),
+            &token_mint,
+            now,
+            ctx.program_id,
+        )?;
+
+        let mut preference = Account::<CompoundPreference>::try_from(&accounts[1])?;
+        require_keys_eq!(
+            preference.owner,
+            holder_account.owner,
+            ErrorCode::InvalidBatchData
+        );
+        require!(
+            preference.enabled && preference.tallied_round == round,
+            ErrorCode::NotCompounding
+        );
+        require!(preference.paid_round != round, ErrorCode::AlreadyTallied);
+        preference.paid_round = round;
+        preference.exit(ctx.program_id)?;
+
+        // calculate expected fee for this leg
+        let fee = get_transfer_fee_config(mint)?
+            .calculate_epoch_fee(epoch, *amount)
+            .ok_or(ErrorCode::FeeCalculationFailed)?;
+
+        transfer_checked_with_fee(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferCheckedWithFee {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    source: ctx.accounts.vault.to_account_info(),
+                    mint: mint.clone(),
+                    destination: accounts[0].clone(),
+                    authority: treasury.to_account_info(),
+                },
+                &[&signer_seeds],
+            ),
+            *amount,  // transfer amount
+            decimals, // decimals
+            fee,      // fee
+        )?;
+    }
+
+    add_to_total(&mut ctx.accounts.tally.paid, total_amount)?;
+
+    emit!(CompoundDistributed {
+        keeper: ctx.accounts.keeper.key(),
+        round,
+        recipients: amounts.len() as u16,
+        total_amount,
+    });
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{allocate, assign, transfer, Allocate, Assign, Transfer},
+};
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use super::{eligibility_rules, eligible_holder};
+use crate::{
+    add_to_total, ErrorCode, ProgramState, RewardStream, Role, StakePool, Stats, StreamPool,
+    StreamedRewardsDistributed, MAX_TRANSFER_BATCH,
+};
+
+#[derive(Accounts)]
+pub struct DistributeStreamed<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::Keeper, keeper.key) @ ErrorCode::UnauthorizedAccess,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Account<'info, Stats>,
+
+    /// CHECK: the stake pool PDA, which may not be initialized yet; stakers' unclaimed rewards stay in the vault
+    #[account(seeds = [b"stake_pool"], bump)]
+    pub stake_pool: UncheckedAccount<'info>,
+
+    /// CHECK: the eligibility rules PDA, which may not be initialized yet
+    #[account(seeds = [b"eligibility"], bump)]
+    pub eligibility: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"stream_pool"], bump = stream_pool.bump)]
+    pub stream_pool: Account<'info, StreamPool>,
+
+    /// Pays for reward streams created on their first distribution
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(address = state.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    /// The reward vault, owned by the program state PDA
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = state,
+        associated_token::token_program = token_program
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = stream_pool.vault)]
+    pub stream_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// like `distribute`, but rewards are credited to each holder's reward stream and unlock linearly
+// over the stream period instead of landing at once
+// remaining accounts are (holder token account, reward stream PDA, compound preference PDA)
+// triples, one per entry in `amounts`, followed by the holder's holding record when a minimum
+// holding age is set; the streams must be writable and are created on first use, and holders
+// who opted into auto-compounding are rejected
+pub fn process_distribute_streamed<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeStreamed<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    require!(!amounts.is_empty(), ErrorCode::EmptyTransferList);
+    require!(
+        amounts.len() <= MAX_TRANSFER_BATCH,
+        ErrorCode::BatchTooLarge
+    );
+
+    let rules = eligibility_rules(&ctx.accounts.eligibility)?;
+    let stride = rules.accounts_per_recipient(3);
+    require!(
+        ctx.remaining_accounts.len() == amounts.len() * stride,
+        ErrorCode::InvalidBatchData
+    );
+
+    let total_amount = amounts
+        .iter()
+        .try_fold(0u64, |total, amount| total.checked_add(*amount))
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let stake_pool = &ctx.accounts.stake_pool;
+    let reserved = if stake_pool.data_is_empty() {
+        0
+    } else {
+        StakePool::try_deserialize(&mut &stake_pool.data.borrow()[..])?.unclaimed_rewards
+    };
+    require!(
+        total_amount <= ctx.accounts.reward_vault.amount.saturating_sub(reserved),
+        ErrorCode::InsufficientRewards
+    );
+
+    let signer_seeds = ctx.accounts.state.signer_seeds();
+
+    // move the whole batch into the stream vault, where it is claimed from
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.stream_vault.to_account_info(),
+                authority: ctx.accounts.state.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        total_amount,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+
+    let token_mint = ctx.accounts.state.token_mint;
+    let period = ctx.accounts.stream_pool.period_seconds;
+    let now = Clock::get()?.unix_timestamp;
+
+    for (accounts, amount) in ctx.remaining_accounts.chunks(stride).zip(amounts.iter()) {
+        let holder_account = eligible_holder(
+            &rules,
+            &accounts[0],
+            Some(&accounts[2]),
+            accounts.get(3),
+            &token_mint,
+            now,
+            ctx.program_id,
+        )?;
+        let owner = holder_account.owner;
+
+        let stream_info = &accounts[1];
+        let (stream_key, bump) =
+            Pubkey::find_program_address(&[b"stream", owner.as_ref()], ctx.program_id);
+        require_keys_eq!(stream_info.key(), stream_key, ErrorCode::InvalidBatchData);
+
+        if stream_info.data_is_empty() {
+            create_stream_account(
+                stream_info,
+                &ctx.accounts.keeper,
+                &ctx.accounts.system_program,
+                &[b"stream", owner.as_ref(), &[bump]],
+                ctx.program_id,
+            )?;
+            RewardStream {
+                owner,
+                locked: 0,
+                unlocked: 0,
+                start_ts: now,
+                end_ts: now,
+                bump,
+            }
+            .try_serialize(&mut &mut stream_info.data.borrow_mut()[..])?;
+        }
+
+        let mut stream = Account::<RewardStream>::try_from(stream_info)?;
+        stream.credit(*amount, now, period)?;
+        stream.exit(ctx.program_id)?;
+    }
+
+    let stats = &mut ctx.accounts.stats;
+    add_to_total(&mut stats.total_rewards_distributed, total_amount)?;
+    add_to_total(&mut stats.distribution_batches, 1)?;
+    stats.last_distribution_ts = now;
+
+    emit!(StreamedRewardsDistributed {
+        keeper: ctx.accounts.keeper.key(),
+        recipients: amounts.len() as u16,
+        total_amount,
+        period_seconds: period,
+    });
+    Ok(())
+}
+
+// `create_account` fails if the address already holds lamports, so anyone could block a holder's
+// stream by pre-funding it; topping up, allocating and assigning works either way
+fn create_stream_account<'info>(
+    stream_info: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    seeds: &[&[u8]],
+    program_id: &Pubkey,
+) -> Result<()> {
+    let lamports = Rent::get()?
+        .minimum_balance(RewardStream::LEN)
+        .saturating_sub(stream_info.lamports());
+    if lamports > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: stream_info.clone(),
+                },
+            ),
+            lamports,
+        )?;
+    }
+
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Allocate {
+                account_to_allocate: stream_info.clone(),
+            },
+            &[seeds],
+        ),
+        RewardStream::LEN as u64,
+    )?;
+    assign(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Assign {
+                account_to_assign: stream_info.clone(),
+            },
+            &[seeds],
+        ),
+        program_id,
+    )
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    add_to_total, ErrorCode, ProgramState, Role, StakePool, StakeRewardsDistributed, Stats,
+    REWARD_PRECISION,
+};
+
+#[derive(Accounts)]
+pub struct DistributeToStakers<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::Keeper, keeper.key) @ ErrorCode::UnauthorizedAccess,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Account<'info, Stats>,
+
+    #[account(mut, seeds = [b"stake_pool"], bump = stake_pool.bump)]
+    pub stake_pool: Account<'info, StakePool>,
+
+    pub keeper: Signer<'info>,
+
+    #[account(address = state.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    /// The reward vault, owned by the program state PDA
+    #[account(
+        associated_token::mint = reward_mint,
+        associated_token::authority = state,
+        associated_token::token_program = token_program
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// sets aside `amount` of the reward vault for stakers, split by boosted stake weight
+// the tokens stay in the reward vault until each staker claims them
+pub fn process_distribute_to_stakers(ctx: Context<DistributeToStakers>, amount: u64) -> Result<()> {
+    let stake_pool = &mut ctx.accounts.stake_pool;
+    require!(stake_pool.total_weight > 0, ErrorCode::NothingStaked);
+
+    let available = ctx
+        .accounts
+        .reward_vault
+        .amount
+        .saturating_sub(stake_pool.unclaimed_rewards);
+    require!(amount <= available, ErrorCode::InsufficientRewards);
+
+    stake_pool.acc_reward_per_weight = stake_pool
+        .acc_reward_per_weight
+        .checked_add(amount as u128 * REWARD_PRECISION / stake_pool.total_weight as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    add_to_total(&mut stake_pool.unclaimed_rewards, amount)?;
+
+    let stats = &mut ctx.accounts.stats;
+    add_to_total(&mut stats.total_rewards_distributed, amount)?;
+    add_to_total(&mut stats.distribution_batches, 1)?;
+    stats.last_distribution_ts = Clock::get()?.unix_timestamp;
+
+    emit!(StakeRewardsDistributed {
+        amount,
+        total_weight: stake_pool.total_weight,
+        acc_reward_per_weight: stake_pool.acc_reward_per_weight,
+    });
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked_with_fee, Mint, Token2022, TokenAccount, TransferCheckedWithFee,
+};
+
+use super::{ensure_rewards_settled, get_transfer_fee_config, set_charity, set_transfer_fee};
+use crate::{
+    add_to_total, CharityRegistry, ErrorCode, Governance, ProgramState, Proposal, ProposalAction,
+    ProposalFinalized, ProposalStatus, Treasury,
+};
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(mut, seeds = [b"program_state"], bump = state.bump)]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        constraint = proposal.status == ProposalStatus::Voting @ ErrorCode::ProposalFinalized
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: the stake pool PDA, which may not be initialized yet
+    #[account(seeds = [b"stake_pool"], bump)]
+    pub stake_pool: UncheckedAccount<'info>,
+    /// CHECK: the stream pool PDA, which may not be initialized yet
+    #[account(seeds = [b"stream_pool"], bump)]
+    pub stream_pool: UncheckedAccount<'info>,
+    /// CHECK: the stream pool's vault, checked in `ensure_rewards_settled`
+    pub stream_vault: UncheckedAccount<'info>,
+    /// CHECK: the current reward mint
+    #[account(address = state.reward_mint)]
+    pub reward_mint: UncheckedAccount<'info>,
+    /// CHECK: the current reward vault, checked in `ensure_rewards_settled`
+    pub reward_vault: UncheckedAccount<'info>,
+
+    /// CHECK: the charity registry PDA, only used by charity share proposals
+    #[account(mut, seeds = [b"charities"], bump)]
+    pub charities: UncheckedAccount<'info>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = governance.vault)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::authority = proposal.proposer,
+        token::token_program = token_program
+    )]
+    pub proposer_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct SweepForfeitedDeposits<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    pub authority: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = governance.vault)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = treasury.vault @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// anyone can finalize a proposal once voting has ended
+// it passes with at least `quorum` votes and more yes than no votes, and its action is applied
+// a passed action that can't be applied marks the proposal failed instead of reverting, so it can
+// still be finalized; the deposit is returned when quorum is reached, otherwise it is forfeited
+pub fn process_execute_proposal(mut ctx: Context<ExecuteProposal>) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+    require!(
+        Clock::get()?.unix_timestamp >= proposal.voting_ends_ts,
+        ErrorCode::VotingNotEnded
+    );
+
+    let total_votes = proposal.yes_votes.saturating_add(proposal.no_votes);
+    let quorum_reached = total_votes >= ctx.accounts.governance.quorum;
+    let passed = quorum_reached && proposal.yes_votes > proposal.no_votes;
+
+    let status = if !passed {
+        ProposalStatus::Rejected
+    } else {
+        let action = proposal.action.clone();
+        match apply_action(&mut ctx, action) {
+            Ok(()) => ProposalStatus::Executed,
+            Err(error) => {
+                msg!("proposal action failed: {}", error);
+                ProposalStatus::Failed
+            }
+        }
+    };
+
+    let deposit = ctx.accounts.proposal.deposit;
+    if quorum_reached && deposit > 0 {
+        // calculate expected fee
+        let epoch = Clock::get()?.epoch;
+        let fee = get_transfer_fee_config(&ctx.accounts.mint_account.to_account_info())?
+            .calculate_epoch_fee(epoch, deposit)
+            .ok_or(ErrorCode::FeeCalculationFailed)?;
+
+        let governance = &ctx.accounts.governance;
+        let signer_seeds = governance.signer_seeds();
+
+        transfer_checked_with_fee(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferCheckedWithFee {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    source: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint_account.to_account_info(),
+                    destination: ctx.accounts.proposer_token_account.to_account_info(),
+                    authority: governance.to_account_info(),
+                },
+                &[&signer_seeds],
+            ),
+            deposit,                            // transfer amount
+            ctx.accounts.mint_account.decimals, // decimals
+            fee,                                // fee
+        )?;
+    } else {
+        add_to_total(&mut ctx.accounts.governance.forfeited, deposit)?;
+    }
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.status = status;
+
+    emit!(ProposalFinalized {
+        id: proposal.id,
+        status: proposal.status,
+        yes_votes: proposal.yes_votes,
+        no_votes: proposal.no_votes,
+    });
+    Ok(())
+}
+
+// token-2022 errors abort the whole transaction, so everything that could make the fee update fail
+// is checked before the CPI
+fn apply_action(ctx: &mut Context<ExecuteProposal>, action: ProposalAction) -> Result<()> {
+    action.validate()?;
+
+    match action {
+        ProposalAction::UpdateFee {
+            transfer_fee_basis_points,
+            maximum_fee,
+        } => {
+            let fee_config = get_transfer_fee_config(&ctx.accounts.mint_account.to_account_info())?;
+            require!(
+                Option::<Pubkey>::from(fee_config.transfer_fee_config_authority)
+                    == Some(ctx.accounts.state.key()),
+                ErrorCode::InvalidProposalAction
+            );
+            set_transfer_fee(
+                &ctx.accounts.state,
+                &ctx.accounts.mint_account,
+                &ctx.accounts.token_program,
+                transfer_fee_basis_points,
+                maximum_fee,
+            )
+        }
+        ProposalAction::SetRewardMint { reward_mint } => {
+            ensure_rewards_settled(
+                &ctx.accounts.state,
+                &ctx.accounts.reward_mint,
+                &ctx.accounts.reward_vault,
+                &ctx.accounts.stake_pool,
+                &ctx.accounts.stream_pool,
+                &ctx.accounts.stream_vault,
+            )?;
+            ctx.accounts.state.reward_mint = reward_mint;
+            Ok(())
+        }
+        ProposalAction::SetCharityShare { wallet, share_bps } => {
+            let charities = &ctx.accounts.charities;
+            require!(!charities.data_is_empty(), ErrorCode::InvalidProposalAction);
+            let mut registry = CharityRegistry::try_deserialize(&mut &charities.data.borrow()[..])?;
+            set_charity(&mut registry, wallet, share_bps, None)?;
+            registry.try_serialize(&mut &mut charities.data.borrow_mut()[..])
+        }
+    }
+}
+
+// deposits of proposals that missed quorum are moved to the treasury vault
+pub fn process_sweep_forfeited_deposits(ctx: Context<SweepForfeitedDeposits>) -> Result<()> {
+    let amount = ctx.accounts.governance.forfeited;
+    require!(amount > 0, ErrorCode::NothingToRelease);
+
+    // calculate expected fee
+    let epoch = Clock::get()?.epoch;
+    let fee = get_transfer_fee_config(&ctx.accounts.mint_account.to_account_info())?
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or(ErrorCode::FeeCalculationFailed)?;
+
+    let governance = &ctx.accounts.governance;
+    let signer_seeds = governance.signer_seeds();
+
+    transfer_checked_with_fee(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferCheckedWithFee {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                source: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint_account.to_account_info(),
+                destination: ctx.accounts.treasury_vault.to_account_info(),
+                authority: governance.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        amount,                             // transfer amount
+        ctx.accounts.mint_account.decimals, // decimals
+        fee,                                // fee
+    )?;
+
+    ctx.accounts.governance.forfeited = 0;
+    Ok(())
+}
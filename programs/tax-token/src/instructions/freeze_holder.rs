@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    freeze_account, thaw_account, FreezeAccount, Mint, ThawAccount, Token2022, TokenAccount,
+};
+
+use crate::{ErrorCode, FreezeAction, FreezeRecord, HolderFreezeUpdated, ProgramState, Role};
+
+#[derive(Accounts)]
+pub struct FreezeHolder<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::Compliance, officer.key) @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init_if_needed,
+        payer = officer,
+        space = FreezeRecord::LEN,
+        seeds = [b"freeze", token_account.key().as_ref()],
+        bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    #[account(
+        init,
+        payer = officer,
+        space = FreezeAction::LEN,
+        seeds = [
+            b"freeze_action",
+            token_account.key().as_ref(),
+            freeze_record.action_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub freeze_action: Account<'info, FreezeAction>,
+
+    #[account(mut)]
+    pub officer: Signer<'info>,
+
+    #[account(
+        address = state.token_mint,
+        constraint = mint_account.freeze_authority == Some(state.key()).into() @ ErrorCode::AuthorityRevoked
+    )]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+// the compliance role can freeze and thaw a holder's token account, signed by the program state PDA
+// every call needs a reason code and gets its own `FreezeAction`, numbered by the token account's
+// `FreezeRecord`, which holds the latest state
+pub fn process_freeze_holder(ctx: Context<FreezeHolder>, reason_code: u16) -> Result<()> {
+    require!(reason_code != 0, ErrorCode::MissingReasonCode);
+
+    let signer_seeds = ctx.accounts.state.signer_seeds();
+
+    freeze_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        FreezeAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint_account.to_account_info(),
+            authority: ctx.accounts.state.to_account_info(),
+        },
+        &[&signer_seeds],
+    ))?;
+
+    ctx.accounts.freeze_record.freeze_count = ctx
+        .accounts
+        .freeze_record
+        .freeze_count
+        .checked_add(1)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    record(ctx, true, reason_code)
+}
+
+pub fn process_thaw_holder(ctx: Context<FreezeHolder>, reason_code: u16) -> Result<()> {
+    require!(reason_code != 0, ErrorCode::MissingReasonCode);
+
+    let signer_seeds = ctx.accounts.state.signer_seeds();
+
+    thaw_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        ThawAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint_account.to_account_info(),
+            authority: ctx.accounts.state.to_account_info(),
+        },
+        &[&signer_seeds],
+    ))?;
+
+    record(ctx, false, reason_code)
+}
+
+fn record(ctx: Context<FreezeHolder>, frozen: bool, reason_code: u16) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    let record = &mut ctx.accounts.freeze_record;
+    let index = record.action_count;
+    record.action_count = index.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+    record.token_account = ctx.accounts.token_account.key();
+    record.owner = ctx.accounts.token_account.owner;
+    record.frozen = frozen;
+    record.reason_code = reason_code;
+    record.officer = ctx.accounts.officer.key();
+    record.updated_ts = timestamp;
+    record.bump = ctx.bumps.freeze_record;
+
+    let action = &mut ctx.accounts.freeze_action;
+    action.token_account = record.token_account;
+    action.index = index;
+    action.frozen = frozen;
+    action.reason_code = reason_code;
+    action.officer = record.officer;
+    action.timestamp = timestamp;
+    action.bump = ctx.bumps.freeze_action;
+
+    emit!(HolderFreezeUpdated {
+        token_account: record.token_account,
+        index,
+        owner: record.owner,
+        officer: record.officer,
+        frozen,
+        reason_code,
+        timestamp,
+    });
+    Ok(())
+}
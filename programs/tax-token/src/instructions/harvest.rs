@@ -3,9 +3,18 @@ use anchor_spl::token_interface::{
     harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint, Mint, Token2022, TokenAccount,
 };
 
+use super::get_transfer_fee_config;
+use crate::{add_to_total, ProgramState, Stats};
+
 #[derive(Accounts)]
 pub struct Harvest<'info> {
-    #[account(mut)]
+    #[account(seeds = [b"program_state"], bump = state.bump)]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Account<'info, Stats>,
+
+    #[account(mut, address = state.token_mint)]
     pub mint_account: InterfaceAccount<'info, Mint>,
     pub token_program: Program<'info, Token2022>,
 }
@@ -15,16 +24,10 @@ pub struct Harvest<'info> {
 pub fn process_harvest<'info>(ctx: Context<'_, '_, 'info, 'info, Harvest<'info>>) -> Result<()> {
     // Using remaining accounts to allow for passing in an unknown number of token accounts to harvest from
     // Check that remaining accounts are token accounts for the mint to harvest to
-    let sources = ctx
-        .remaining_accounts
-        .iter()
-        .filter_map(|account| {
-            InterfaceAccount::<TokenAccount>::try_from(account)
-                .ok()
-                .filter(|token_account| token_account.mint == ctx.accounts.mint_account.key())
-                .map(|_| account.to_account_info())
-        })
-        .collect::<Vec<_>>();
+    let sources = token_accounts_for_mint(ctx.remaining_accounts, &ctx.accounts.mint_account.key());
+
+    let mint = ctx.accounts.mint_account.to_account_info();
+    let withheld_before = mint_withheld_amount(&mint)?;
 
     harvest_withheld_tokens_to_mint(
         CpiContext::new(
@@ -36,5 +39,29 @@ pub fn process_harvest<'info>(ctx: Context<'_, '_, 'info, 'info, Harvest<'info>>
         ),
         sources, // token accounts to harvest from
     )?;
+
+    let harvested = mint_withheld_amount(&mint)? - withheld_before;
+    add_to_total(&mut ctx.accounts.stats.total_tax_harvested, harvested)?;
     Ok(())
 }
+
+// transfer fees harvested to the mint account and not yet withdrawn
+pub fn mint_withheld_amount(mint: &AccountInfo) -> Result<u64> {
+    Ok(get_transfer_fee_config(mint)?.withheld_amount.into())
+}
+
+// filters the given accounts down to token accounts for `mint`
+pub fn token_accounts_for_mint<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    mint: &Pubkey,
+) -> Vec<AccountInfo<'info>> {
+    accounts
+        .iter()
+        .filter_map(|account| {
+            InterfaceAccount::<TokenAccount>::try_from(account)
+                .ok()
+                .filter(|token_account| token_account.mint == *mint)
+                .map(|_| account.to_account_info())
+        })
+        .collect::<Vec<_>>()
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, harvest_withheld_tokens_to_mint, CloseAccount, HarvestWithheldTokensToMint,
+    Mint, Token2022, TokenAccount,
+};
+
+use super::mint_withheld_amount;
+use crate::{add_to_total, ErrorCode, ProgramState, Stats};
+
+#[derive(Accounts)]
+pub struct HarvestAndClose<'info> {
+    #[account(seeds = [b"program_state"], bump = state.bump)]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Account<'info, Stats>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::authority = owner,
+        token::token_program = token_program,
+        constraint = token_account.amount == 0 @ ErrorCode::TokenAccountNotEmpty
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// token accounts can not be closed while they still hold withheld transfer fees
+// holders harvest their own withheld fees to the mint account, then close the empty account
+// the rent is returned to the owner
+pub fn process_harvest_and_close(ctx: Context<HarvestAndClose>) -> Result<()> {
+    let mint = ctx.accounts.mint_account.to_account_info();
+    let withheld_before = mint_withheld_amount(&mint)?;
+
+    harvest_withheld_tokens_to_mint(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            HarvestWithheldTokensToMint {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                mint: ctx.accounts.mint_account.to_account_info(),
+            },
+        ),
+        vec![ctx.accounts.token_account.to_account_info()], // token account to harvest from
+    )?;
+
+    let harvested = mint_withheld_amount(&mint)? - withheld_before;
+    add_to_total(&mut ctx.accounts.stats.total_tax_harvested, harvested)?;
+
+    close_account(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        },
+    ))?;
+    Ok(())
+}
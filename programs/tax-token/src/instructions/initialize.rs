@@ -1,11 +1,14 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
 use anchor_lang::solana_program::rent::{
     DEFAULT_EXEMPTION_THRESHOLD, DEFAULT_LAMPORTS_PER_BYTE_YEAR,
 };
 use anchor_lang::system_program::{create_account, CreateAccount};
 use anchor_lang::system_program::{transfer, Transfer};
 use anchor_spl::token_interface::{
-    metadata_pointer_initialize, token_metadata_initialize, MetadataPointerInitialize, Token2022,
+    default_account_state_initialize, group_member_pointer_initialize, group_pointer_initialize,
+    metadata_pointer_initialize, token_metadata_initialize, DefaultAccountStateInitialize,
+    GroupMemberPointerInitialize, GroupPointerInitialize, MetadataPointerInitialize, Token2022,
     TokenMetadataInitialize,
 };
 use anchor_spl::{associated_token::AssociatedToken, token::Mint as TokenMint};
@@ -15,11 +18,14 @@ use anchor_spl::{
         initialize_mint2,
         spl_token_2022::{
             extension::{
+                confidential_transfer::instruction::initialize_mint as confidential_transfer_initialize,
+                confidential_transfer_fee::instruction::initialize_confidential_transfer_fee_config,
                 transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
                 StateWithExtensions,
             },
             pod::PodMint,
-            state::Mint as MintState,
+            solana_zk_token_sdk::zk_token_elgamal::pod::ElGamalPubkey,
+            state::{AccountState, Mint as MintState},
         },
         InitializeMint2,
     },
@@ -30,22 +36,56 @@ use anchor_spl::{
 };
 use spl_token_metadata_interface::state::TokenMetadata;
 
-use crate::{InitTokenParams, ProgramState, TAX_BASIS_POINT};
+use crate::{ErrorCode, InitTokenParams, ProgramState, Stats, TAX_BASIS_POINT};
 
 pub fn process_initialize(ctx: Context<Initialize>, params: InitTokenParams) -> Result<()> {
     msg!("Initializing SPL token with 10% tax");
 
+    // The total supply is the immutable cap for `mint_to`
+    let max_supply =
+        u64::try_from(params.total_supply).map_err(|_| ErrorCode::InvalidTokenSupply)?;
+    require!(max_supply > 0, ErrorCode::InvalidTokenSupply);
+
     // Initialize the program state
+    // All roles start with the authority, which can grant them to other keys afterwards
     let state = &mut ctx.accounts.state;
     state.authority = ctx.accounts.authority.key();
     state.token_mint = ctx.accounts.token_mint.key();
     state.reward_mint = ctx.accounts.reward_mint.key();
+    state.fee_admin = ctx.accounts.authority.key();
+    state.treasury_admin = ctx.accounts.authority.key();
+    state.metadata_admin = ctx.accounts.authority.key();
+    state.keeper = ctx.accounts.authority.key();
+    state.pauser = ctx.accounts.authority.key();
+    state.compliance = ctx.accounts.authority.key();
+    state.reward_admin = ctx.accounts.authority.key();
+    state.paused = false;
+    state.max_supply = max_supply;
+    state.bump = ctx.bumps.state;
+
+    // Lifetime totals start at zero
+    ctx.accounts.stats.bump = ctx.bumps.stats;
 
     // Calculate space required for mint with both TransferFeeConfig and MetadataPointer extensions
-    let mint_size = ExtensionType::try_calculate_account_len::<PodMint>(&[
+    // (plus DefaultAccountState if enabled)
+    let mut extensions = vec![
         ExtensionType::TransferFeeConfig,
         ExtensionType::MetadataPointer,
-    ])?;
+    ];
+    if params.default_account_state {
+        extensions.push(ExtensionType::DefaultAccountState);
+    }
+    if params.confidential_transfer.is_some() {
+        extensions.push(ExtensionType::ConfidentialTransferMint);
+        extensions.push(ExtensionType::ConfidentialTransferFeeConfig);
+    }
+    if params.group_pointer {
+        extensions.push(ExtensionType::GroupPointer);
+    }
+    if params.group_member_pointer {
+        extensions.push(ExtensionType::GroupMemberPointer);
+    }
+    let mint_size = ExtensionType::try_calculate_account_len::<PodMint>(&extensions)?;
 
     // Calculate minimum lamports required for size of mint account with extensions
     let lamports = (Rent::get()?).minimum_balance(mint_size);
@@ -67,6 +107,7 @@ pub fn process_initialize(ctx: Context<Initialize>, params: InitTokenParams) ->
     }
 
     // Initialize the TransferFeeConfig extension
+    // The program state PDA holds the fee authorities, so fee updates and withdrawals go through role checks
     transfer_fee_initialize(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
@@ -75,10 +116,10 @@ pub fn process_initialize(ctx: Context<Initialize>, params: InitTokenParams) ->
                 mint: ctx.accounts.token_mint.to_account_info(),
             },
         ),
-        Some(&ctx.accounts.authority.key()), // Transfer fee config authority
-        Some(&ctx.accounts.authority.key()), // Withdraw authority
-        TAX_BASIS_POINT,                     // Transfer fee basis points
-        (params.total_supply / 10) as u64,   // Maximum fee
+        Some(&ctx.accounts.state.key()), // Transfer fee config authority
+        Some(&ctx.accounts.state.key()), // Withdraw authority
+        TAX_BASIS_POINT,                 // Transfer fee basis points
+        max_supply / 10,                 // Maximum fee
     )?;
 
     // Initialize the MetadataPointer extension BEFORE initializing the mint
@@ -90,11 +131,81 @@ pub fn process_initialize(ctx: Context<Initialize>, params: InitTokenParams) ->
                 mint: ctx.accounts.token_mint.to_account_info(),
             },
         ),
-        Some(ctx.accounts.authority.key()), // Authority for metadata updates
+        Some(ctx.accounts.state.key()), // Authority for metadata updates
         Some(ctx.accounts.token_mint.key()), // Metadata stored in the mint account itself
     )?;
 
+    // Initialize the DefaultAccountState extension, so `pause` can make new token accounts start frozen
+    if params.default_account_state {
+        default_account_state_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                DefaultAccountStateInitialize {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                },
+            ),
+            &AccountState::Initialized,
+        )?;
+    }
+
+    // Initialize the ConfidentialTransferMint and ConfidentialTransferFeeConfig extensions, so holders can
+    // use confidential balances while fees are still withheld, encrypted under the withdraw ElGamal key
+    if let Some(confidential) = &params.confidential_transfer {
+        invoke(
+            &confidential_transfer_initialize(
+                &ctx.accounts.token_program.key(),
+                &ctx.accounts.token_mint.key(),
+                Some(ctx.accounts.state.key()), // Authority for confidential transfer settings
+                confidential.auto_approve_new_accounts,
+                confidential.auditor_elgamal_pubkey.map(ElGamalPubkey),
+            )?,
+            &[ctx.accounts.token_mint.to_account_info()],
+        )?;
+
+        invoke(
+            &initialize_confidential_transfer_fee_config(
+                &ctx.accounts.token_program.key(),
+                &ctx.accounts.token_mint.key(),
+                Some(ctx.accounts.state.key()), // Authority for confidential fee settings
+                ElGamalPubkey(confidential.withdraw_withheld_authority_elgamal_pubkey),
+            )?,
+            &[ctx.accounts.token_mint.to_account_info()],
+        )?;
+    }
+
+    // Initialize the GroupPointer and GroupMemberPointer extensions, pointing at the mint itself
+    // `create_token_group` then makes this mint the group of a family of tax tokens, and `join_token_group`
+    // makes it a member of one
+    if params.group_pointer {
+        group_pointer_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                GroupPointerInitialize {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                },
+            ),
+            Some(ctx.accounts.state.key()), // Authority for group pointer updates
+            Some(ctx.accounts.token_mint.key()), // Group data stored in the mint account itself
+        )?;
+    }
+    if params.group_member_pointer {
+        group_member_pointer_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                GroupMemberPointerInitialize {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                },
+            ),
+            Some(ctx.accounts.state.key()), // Authority for member pointer updates
+            Some(ctx.accounts.token_mint.key()), // Member data stored in the mint account itself
+        )?;
+    }
+
     // Initialize the mint data (AFTER all extensions are set up)
+    // The program state PDA is the mint and freeze authority, so supply changes go through `mint_to`
     initialize_mint2(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
@@ -103,8 +214,8 @@ pub fn process_initialize(ctx: Context<Initialize>, params: InitTokenParams) ->
             },
         ),
         params.decimals,
-        &ctx.accounts.authority.key(),
-        Some(&ctx.accounts.authority.key()),
+        &ctx.accounts.state.key(),
+        Some(&ctx.accounts.state.key()),
     )?;
 
     ctx.accounts.check_mint_data()?;
@@ -134,17 +245,19 @@ pub fn process_initialize(ctx: Context<Initialize>, params: InitTokenParams) ->
         additional_lamports,
     )?;
 
-    // Initialize token metadata
+    // Initialize token metadata, signed by the program state PDA as mint authority
+    let signer_seeds = ctx.accounts.state.signer_seeds();
     token_metadata_initialize(
-        CpiContext::new(
+        CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             TokenMetadataInitialize {
                 token_program_id: ctx.accounts.token_program.to_account_info(),
                 mint: ctx.accounts.token_mint.to_account_info(),
                 metadata: ctx.accounts.token_mint.to_account_info(),
-                mint_authority: ctx.accounts.authority.to_account_info(),
-                update_authority: ctx.accounts.authority.to_account_info(),
+                mint_authority: ctx.accounts.state.to_account_info(),
+                update_authority: ctx.accounts.state.to_account_info(),
             },
+            &[&signer_seeds],
         ),
         params.name,
         params.symbol,
@@ -167,6 +280,15 @@ pub struct Initialize<'info> {
     )]
     pub state: Account<'info, ProgramState>,
 
+    #[account(
+        init,
+        payer = authority,
+        space = Stats::LEN,
+        seeds = [b"stats"],
+        bump
+    )]
+    pub stats: Account<'info, Stats>,
+
     #[account(mut)]
     pub token_mint: Signer<'info>,
 
@@ -198,12 +320,12 @@ impl<'info> Initialize<'info> {
 
         assert_eq!(
             extension_data.transfer_fee_config_authority,
-            OptionalNonZeroPubkey::try_from(Some(self.authority.key()))?
+            OptionalNonZeroPubkey::try_from(Some(self.state.key()))?
         );
 
         assert_eq!(
             extension_data.withdraw_withheld_authority,
-            OptionalNonZeroPubkey::try_from(Some(self.authority.key()))?
+            OptionalNonZeroPubkey::try_from(Some(self.state.key()))?
         );
 
         msg!("Extension Data: {:?}", extension_data);
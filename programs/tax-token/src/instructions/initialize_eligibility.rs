@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{Eligibility, ErrorCode, ProgramState};
+
+#[derive(Accounts)]
+pub struct InitializeEligibility<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Eligibility::LEN,
+        seeds = [b"eligibility"],
+        bump
+    )]
+    pub eligibility: Account<'info, Eligibility>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// reward eligibility rules read by `distribute`; until they exist every holder with a positive balance is eligible
+pub fn process_initialize_eligibility(
+    ctx: Context<InitializeEligibility>,
+    min_balance: u64,
+    min_holding_seconds: i64,
+) -> Result<()> {
+    require!(min_holding_seconds >= 0, ErrorCode::InvalidHoldingPeriod);
+
+    let eligibility = &mut ctx.accounts.eligibility;
+    eligibility.min_balance = min_balance;
+    eligibility.min_holding_seconds = min_holding_seconds;
+    eligibility.excluded = Vec::new();
+    eligibility.bump = ctx.bumps.eligibility;
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+use crate::{ErrorCode, Governance, GovernanceParams, ProgramState};
+
+#[derive(Accounts)]
+pub struct InitializeGovernance<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Governance::LEN,
+        seeds = [b"governance"],
+        bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    /// The governance vault holding proposal deposits, owned by the governance PDA
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint_account,
+        associated_token::authority = governance,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGovernance<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+
+    pub authority: Signer<'info>,
+}
+
+// holders can propose fee, reward mint and charity share changes, vote with their staked tokens,
+// and execute passed proposals without the admin
+pub fn process_initialize_governance(
+    ctx: Context<InitializeGovernance>,
+    params: GovernanceParams,
+) -> Result<()> {
+    require!(
+        params.voting_period > 0 && params.quorum > 0,
+        ErrorCode::InvalidGovernanceParams
+    );
+
+    let governance = &mut ctx.accounts.governance;
+    governance.vault = ctx.accounts.vault.key();
+    governance.proposal_deposit = params.proposal_deposit;
+    governance.voting_period = params.voting_period;
+    governance.quorum = params.quorum;
+    governance.proposal_count = 0;
+    governance.forfeited = 0;
+    governance.bump = ctx.bumps.governance;
+    Ok(())
+}
+
+// only affects proposals created from now on
+pub fn process_update_governance(
+    ctx: Context<UpdateGovernance>,
+    params: GovernanceParams,
+) -> Result<()> {
+    require!(
+        params.voting_period > 0 && params.quorum > 0,
+        ErrorCode::InvalidGovernanceParams
+    );
+
+    let governance = &mut ctx.accounts.governance;
+    governance.proposal_deposit = params.proposal_deposit;
+    governance.voting_period = params.voting_period;
+    governance.quorum = params.quorum;
+    Ok(())
+}
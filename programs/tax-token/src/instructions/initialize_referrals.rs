@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+use crate::{ErrorCode, ProgramState, ReferralConfig};
+
+#[derive(Accounts)]
+pub struct InitializeReferrals<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ReferralConfig::LEN,
+        seeds = [b"referral_config"],
+        bump
+    )]
+    pub referral_config: Account<'info, ReferralConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    /// The referral vault, owned by the referral config PDA
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint_account,
+        associated_token::authority = referral_config,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateReferralShare<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"referral_config"], bump = referral_config.bump)]
+    pub referral_config: Account<'info, ReferralConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+// referrers are credited `share_bps` of the fee withheld on transfers they refer
+// the referral vault is funded separately, e.g. with `treasury_spend` from the withdrawn tax
+pub fn process_initialize_referrals(
+    ctx: Context<InitializeReferrals>,
+    share_bps: u16,
+) -> Result<()> {
+    require!(share_bps <= 10_000, ErrorCode::InvalidReferralShare);
+
+    let referral_config = &mut ctx.accounts.referral_config;
+    referral_config.vault = ctx.accounts.vault.key();
+    referral_config.share_bps = share_bps;
+    referral_config.total_owed = 0;
+    referral_config.bump = ctx.bumps.referral_config;
+    Ok(())
+}
+
+pub fn process_update_referral_share(
+    ctx: Context<UpdateReferralShare>,
+    share_bps: u16,
+) -> Result<()> {
+    require!(share_bps <= 10_000, ErrorCode::InvalidReferralShare);
+
+    ctx.accounts.referral_config.share_bps = share_bps;
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+use crate::{ErrorCode, ProgramState, StakePool};
+
+#[derive(Accounts)]
+pub struct InitializeStaking<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = StakePool::LEN,
+        seeds = [b"stake_pool"],
+        bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    /// The stake vault, owned by the stake pool PDA
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint_account,
+        associated_token::authority = stake_pool,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process_initialize_staking(ctx: Context<InitializeStaking>) -> Result<()> {
+    let stake_pool = &mut ctx.accounts.stake_pool;
+    stake_pool.vault = ctx.accounts.vault.key();
+    stake_pool.total_staked = 0;
+    stake_pool.total_weight = 0;
+    stake_pool.acc_reward_per_weight = 0;
+    stake_pool.unclaimed_rewards = 0;
+    stake_pool.bump = ctx.bumps.stake_pool;
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{ErrorCode, ProgramState, StreamPool};
+
+#[derive(Accounts)]
+pub struct InitializeStreaming<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = StreamPool::LEN,
+        seeds = [b"stream_pool"],
+        bump
+    )]
+    pub stream_pool: Account<'info, StreamPool>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(address = state.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    /// The stream vault, owned by the stream pool PDA
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = reward_mint,
+        associated_token::authority = stream_pool,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateStreamPeriod<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"stream_pool"], bump = stream_pool.bump)]
+    pub stream_pool: Account<'info, StreamPool>,
+
+    pub authority: Signer<'info>,
+}
+
+// streamed rewards are moved out of the reward vault into the stream vault when distributed,
+// and unlock linearly over `period_seconds`
+pub fn process_initialize_streaming(
+    ctx: Context<InitializeStreaming>,
+    period_seconds: i64,
+) -> Result<()> {
+    require!(period_seconds > 0, ErrorCode::InvalidStreamPeriod);
+
+    let stream_pool = &mut ctx.accounts.stream_pool;
+    stream_pool.vault = ctx.accounts.vault.key();
+    stream_pool.period_seconds = period_seconds;
+    stream_pool.bump = ctx.bumps.stream_pool;
+    Ok(())
+}
+
+// only affects rewards distributed from now on
+pub fn process_update_stream_period(
+    ctx: Context<UpdateStreamPeriod>,
+    period_seconds: i64,
+) -> Result<()> {
+    require!(period_seconds > 0, ErrorCode::InvalidStreamPeriod);
+
+    ctx.accounts.stream_pool.period_seconds = period_seconds;
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+use crate::{ErrorCode, ProgramState, Treasury};
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Treasury::LEN,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    /// The treasury vault, owned by the treasury PDA
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint_account,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// withdrawn tax lands in the treasury vault, which can only be spent through `treasury_spend`
+pub fn process_initialize_treasury(
+    ctx: Context<InitializeTreasury>,
+    spend_limit: u64,
+    window_seconds: i64,
+) -> Result<()> {
+    require!(window_seconds > 0, ErrorCode::InvalidSpendWindow);
+
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.vault = ctx.accounts.vault.key();
+    treasury.spend_limit = spend_limit;
+    treasury.window_seconds = window_seconds;
+    treasury.allowance = spend_limit;
+    treasury.refilled_at = Clock::get()?.unix_timestamp;
+    treasury.allowlist = Vec::new();
+    treasury.bump = ctx.bumps.treasury;
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::{ErrorCode, PositionLock, PositionLocked, PositionUnlocked, RAYDIUM_CLMM_PROGRAM_IDS};
+
+#[derive(Accounts)]
+pub struct LockPosition<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = PositionLock::LEN,
+        seeds = [b"position_lock", position_mint.key().as_ref()],
+        bump
+    )]
+    pub lock: Account<'info, PositionLock>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The CLMM position NFT mint
+    #[account(
+        mint::token_program = token_program,
+        constraint = position_mint.decimals == 0 && position_mint.supply == 1 @ ErrorCode::InvalidPosition
+    )]
+    pub position_mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: the position's CLMM personal position PDA, checked against the NFT mint in the processor
+    pub personal_position: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = position_mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub owner_position_account: InterfaceAccount<'info, TokenAccount>,
+    /// The escrow holding the position NFT, owned by the lock PDA
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = position_mint,
+        associated_token::authority = lock,
+        associated_token::token_program = token_program
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendLock<'info> {
+    #[account(
+        mut,
+        seeds = [b"position_lock", lock.position_mint.as_ref()],
+        bump = lock.bump,
+        has_one = owner
+    )]
+    pub lock: Account<'info, PositionLock>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UnlockPosition<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"position_lock", position_mint.key().as_ref()],
+        bump = lock.bump,
+        has_one = owner,
+        has_one = position_mint,
+        has_one = escrow
+    )]
+    pub lock: Account<'info, PositionLock>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub position_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = position_mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub owner_position_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// escrows a CLMM position NFT until `unlock_ts`, so holders can verify the liquidity can't be pulled
+// while it is locked, the position's trading fees can only be collected to the treasury
+pub fn process_lock_position(ctx: Context<LockPosition>, unlock_ts: i64) -> Result<()> {
+    require!(
+        unlock_ts > Clock::get()?.unix_timestamp,
+        ErrorCode::InvalidUnlockTime
+    );
+
+    // only a real CLMM position NFT has a personal position at `[b"position", nft_mint]`
+    let personal_position = &ctx.accounts.personal_position;
+    let clmm_program = personal_position.owner;
+    require!(
+        RAYDIUM_CLMM_PROGRAM_IDS.contains(clmm_program) && !personal_position.data_is_empty(),
+        ErrorCode::InvalidPosition
+    );
+    require_keys_eq!(
+        personal_position.key(),
+        Pubkey::find_program_address(
+            &[b"position", ctx.accounts.position_mint.key().as_ref()],
+            clmm_program
+        )
+        .0,
+        ErrorCode::InvalidPosition
+    );
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.owner_position_account.to_account_info(),
+                mint: ctx.accounts.position_mint.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        1,
+        ctx.accounts.position_mint.decimals,
+    )?;
+
+    let lock = &mut ctx.accounts.lock;
+    lock.owner = ctx.accounts.owner.key();
+    lock.position_mint = ctx.accounts.position_mint.key();
+    lock.escrow = ctx.accounts.escrow.key();
+    lock.unlock_ts = unlock_ts;
+    lock.bump = ctx.bumps.lock;
+
+    emit!(PositionLocked {
+        owner: lock.owner,
+        position_mint: lock.position_mint,
+        unlock_ts,
+    });
+    Ok(())
+}
+
+// a lock can only ever be extended
+pub fn process_extend_lock(ctx: Context<ExtendLock>, unlock_ts: i64) -> Result<()> {
+    let lock = &mut ctx.accounts.lock;
+    require!(unlock_ts > lock.unlock_ts, ErrorCode::InvalidUnlockTime);
+    lock.unlock_ts = unlock_ts;
+
+    emit!(PositionLocked {
+        owner: lock.owner,
+        position_mint: lock.position_mint,
+        unlock_ts,
+    });
+    Ok(())
+}
+
+// returns the position NFT to its owner once the lock has expired and closes the escrow
+pub fn process_unlock_position(ctx: Context<UnlockPosition>) -> Result<()> {
+    let lock = &ctx.accounts.lock;
+    require!(
+        Clock::get()?.unix_timestamp >= lock.unlock_ts,
+        ErrorCode::PositionLocked
+    );
+
+    let signer_seeds = lock.signer_seeds();
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow.to_account_info(),
+                mint: ctx.accounts.position_mint.to_account_info(),
+                to: ctx.accounts.owner_position_account.to_account_info(),
+                authority: lock.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        ctx.accounts.escrow.amount,
+        ctx.accounts.position_mint.decimals,
+    )?;
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: lock.to_account_info(),
+        },
+        &[&signer_seeds],
+    ))?;
+
+    emit!(PositionUnlocked {
+        owner: lock.owner,
+        position_mint: lock.position_mint,
+    });
+    Ok(())
+}
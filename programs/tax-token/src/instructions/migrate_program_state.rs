@@ -0,0 +1,173 @@
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::{prelude::*, Discriminator};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{BaseStateWithExtensions, StateWithExtensions},
+    instruction::AuthorityType,
+    state::Mint as MintState,
+};
+use anchor_spl::token_interface::{
+    set_authority, spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_metadata_interface::state::TokenMetadata, token_metadata_update_authority, Mint,
+    SetAuthority, Token2022, TokenMetadataUpdateAuthority,
+};
+
+use super::get_transfer_fee_config;
+use crate::{ErrorCode, ProgramState, Stats};
+
+#[derive(Accounts)]
+pub struct MigrateProgramState<'info> {
+    /// CHECK: the program state in its original layout, which doesn't deserialize as `ProgramState`;
+    /// its discriminator, length and authority are checked in the processor
+    #[account(mut, seeds = [b"program_state"], bump, owner = crate::ID)]
+    pub state: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Stats::LEN,
+        seeds = [b"stats"],
+        bump
+    )]
+    pub stats: Account<'info, Stats>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The token mint, checked against the original state in the processor
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+// moves a deployment from the original program state (authority, token mint, reward mint) to the
+// current layout: the account is grown in place, every role starts with the authority as at
+// `initialize`, and the lifetime totals start at zero
+// the original deployment left the mint's authorities with the authority wallet, while the current
+// program signs for the mint as the program state PDA, so they are moved to it here
+pub fn process_migrate_program_state(
+    ctx: Context<MigrateProgramState>,
+    max_supply: u64,
+) -> Result<()> {
+    let state_info = ctx.accounts.state.to_account_info();
+    let (authority, token_mint, reward_mint) = {
+        let data = state_info.try_borrow_data()?;
+        require!(
+            data.len() == ProgramState::LEGACY_LEN && data[..8] == ProgramState::DISCRIMINATOR,
+            ErrorCode::AlreadyMigrated
+        );
+        <(Pubkey, Pubkey, Pubkey)>::deserialize(&mut &data[8..])?
+    };
+    require_keys_eq!(
+        authority,
+        ctx.accounts.authority.key(),
+        ErrorCode::UnauthorizedAccess
+    );
+    require_keys_eq!(
+        ctx.accounts.mint_account.key(),
+        token_mint,
+        ErrorCode::InvalidBatchData
+    );
+    require!(
+        max_supply > 0 && max_supply >= ctx.accounts.mint_account.supply,
+        ErrorCode::InvalidTokenSupply
+    );
+    move_mint_authorities(&ctx, authority)?;
+
+    let lamports = Rent::get()?
+        .minimum_balance(ProgramState::LEN)
+        .saturating_sub(state_info.lamports());
+    if lamports > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: state_info.clone(),
+                },
+            ),
+            lamports,
+        )?;
+    }
+    state_info.realloc(ProgramState::LEN, false)?;
+
+    let state = ProgramState {
+        authority,
+        token_mint,
+        reward_mint,
+        fee_admin: authority,
+        treasury_admin: authority,
+        metadata_admin: authority,
+        keeper: authority,
+        pauser: authority,
+        compliance: authority,
+        reward_admin: authority,
+        paused: false,
+        max_supply,
+        bump: ctx.bumps.state,
+    };
+    state.try_serialize(&mut &mut state_info.try_borrow_mut_data()?[..])?;
+
+    ctx.accounts.stats.bump = ctx.bumps.stats;
+    Ok(())
+}
+
+// authorities that were already moved to the program state PDA or revoked are left as they are
+fn move_mint_authorities(ctx: &Context<MigrateProgramState>, authority: Pubkey) -> Result<()> {
+    let state = ctx.accounts.state.key();
+    let mint = &ctx.accounts.mint_account;
+    let fee_config = get_transfer_fee_config(&mint.to_account_info())?;
+    let authorities = [
+        (AuthorityType::MintTokens, mint.mint_authority.into()),
+        (AuthorityType::FreezeAccount, mint.freeze_authority.into()),
+        (
+            AuthorityType::TransferFeeConfig,
+            Option::<Pubkey>::from(fee_config.transfer_fee_config_authority),
+        ),
+        (
+            AuthorityType::WithheldWithdraw,
+            Option::<Pubkey>::from(fee_config.withdraw_withheld_authority),
+        ),
+    ];
+    for (authority_type, current) in authorities {
+        if current != Some(authority) {
+            continue;
+        }
+        set_authority(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                SetAuthority {
+                    current_authority: ctx.accounts.authority.to_account_info(),
+                    account_or_mint: mint.to_account_info(),
+                },
+            ),
+            authority_type,
+            Some(state),
+        )?;
+    }
+
+    let metadata_authority = {
+        let mint_info = mint.to_account_info();
+        let mint_data = mint_info.data.borrow();
+        let mint_with_extension = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+        mint_with_extension
+            .get_variable_len_extension::<TokenMetadata>()
+            .ok()
+            .and_then(|metadata| Option::<Pubkey>::from(metadata.update_authority))
+    };
+    if metadata_authority == Some(authority) {
+        token_metadata_update_authority(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TokenMetadataUpdateAuthority {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    metadata: mint.to_account_info(),
+                    current_authority: ctx.accounts.authority.to_account_info(),
+                    new_authority: ctx.accounts.state.to_account_info(),
+                },
+            ),
+            OptionalNonZeroPubkey::try_from(Some(state))?,
+        )?;
+    }
+    Ok(())
+}
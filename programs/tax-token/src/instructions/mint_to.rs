@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, Token2022, TokenAccount};
+
+use crate::{ErrorCode, ProgramState, TokensMinted};
+
+#[derive(Accounts)]
+pub struct MintTo<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        address = state.token_mint,
+        constraint = mint_account.mint_authority == Some(state.key()).into() @ ErrorCode::AuthorityRevoked
+    )]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// the program state PDA is the mint authority, so the max supply set at initialize can not be exceeded
+pub fn process_mint_to(ctx: Context<MintTo>, amount: u64) -> Result<()> {
+    mint_capped(
+        &ctx.accounts.state,
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account.to_account_info(),
+        &ctx.accounts.token_program,
+        amount,
+    )
+}
+
+// mints `amount` to `destination`, signed by the program state PDA, within the max supply
+pub fn mint_capped<'info>(
+    state: &Account<'info, ProgramState>,
+    mint_account: &InterfaceAccount<'info, Mint>,
+    destination: &AccountInfo<'info>,
+    token_program: &Program<'info, Token2022>,
+    amount: u64,
+) -> Result<()> {
+    let supply = mint_account
+        .supply
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    require!(supply <= state.max_supply, ErrorCode::MaxSupplyExceeded);
+
+    let signer_seeds = state.signer_seeds();
+
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token_interface::MintTo {
+                mint: mint_account.to_account_info(),
+                to: destination.clone(),
+                authority: state.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        amount,
+    )?;
+
+    emit!(TokensMinted {
+        destination: destination.key(),
+        amount,
+        supply,
+        max_supply: state.max_supply,
+    });
+    Ok(())
+}
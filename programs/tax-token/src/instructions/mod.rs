@@ -4,14 +4,164 @@ pub use initialize::*;
 pub mod update_program_state;
 pub use update_program_state::*;
 
+pub mod migrate_program_state;
+pub use migrate_program_state::*;
+
 pub mod transfer;
 pub use transfer::*;
 
+pub mod transfer_many;
+pub use transfer_many::*;
+
+pub mod quote_transfer;
+pub use quote_transfer::*;
+
 pub mod harvest;
 pub use harvest::*;
 
+pub mod harvest_and_close;
+pub use harvest_and_close::*;
+
 pub mod update_fee;
 pub use update_fee::*;
 
 pub mod withdraw;
 pub use withdraw::*;
+
+pub mod withdraw_from_accounts;
+pub use withdraw_from_accounts::*;
+
+pub mod update_metadata;
+pub use update_metadata::*;
+
+pub mod distribute;
+pub use distribute::*;
+
+pub mod deposit_rewards;
+pub use deposit_rewards::*;
+
+pub mod burn_tax;
+pub use burn_tax::*;
+
+pub mod initialize_treasury;
+pub use initialize_treasury::*;
+
+pub mod update_treasury;
+pub use update_treasury::*;
+
+pub mod treasury_spend;
+pub use treasury_spend::*;
+
+pub mod initialize_eligibility;
+pub use initialize_eligibility::*;
+
+pub mod update_eligibility;
+pub use update_eligibility::*;
+
+pub mod checkpoint_holding;
+pub use checkpoint_holding::*;
+
+pub mod reward_recipient;
+pub use reward_recipient::*;
+
+pub mod auto_compound;
+pub use auto_compound::*;
+
+pub mod tally_compound;
+pub use tally_compound::*;
+
+pub mod distribute_compound;
+pub use distribute_compound::*;
+
+pub mod initialize_streaming;
+pub use initialize_streaming::*;
+
+pub mod distribute_streamed;
+pub use distribute_streamed::*;
+
+pub mod claim_streamed;
+pub use claim_streamed::*;
+
+pub mod initialize_referrals;
+pub use initialize_referrals::*;
+
+pub mod register_referrer;
+pub use register_referrer::*;
+
+pub mod claim_referral;
+pub use claim_referral::*;
+
+pub mod start_lottery;
+pub use start_lottery::*;
+
+pub mod reveal_lottery;
+pub use reveal_lottery::*;
+
+pub mod pay_lottery_winner;
+pub use pay_lottery_winner::*;
+
+pub mod update_charities;
+pub use update_charities::*;
+
+pub mod initialize_governance;
+pub use initialize_governance::*;
+
+pub mod create_proposal;
+pub use create_proposal::*;
+
+pub mod cast_vote;
+pub use cast_vote::*;
+
+pub mod execute_proposal;
+pub use execute_proposal::*;
+
+pub mod initialize_staking;
+pub use initialize_staking::*;
+
+pub mod stake;
+pub use stake::*;
+
+pub mod claim_stake_rewards;
+pub use claim_stake_rewards::*;
+
+pub mod distribute_to_stakers;
+pub use distribute_to_stakers::*;
+
+pub mod create_vesting;
+pub use create_vesting::*;
+
+pub mod release_vested;
+pub use release_vested::*;
+
+pub mod revoke_vesting;
+pub use revoke_vesting::*;
+
+pub mod sale;
+pub use sale::*;
+
+pub mod lock_position;
+pub use lock_position::*;
+
+pub mod collect_fees_while_locked;
+pub use collect_fees_while_locked::*;
+
+pub mod confidential_fees;
+pub use confidential_fees::*;
+
+pub mod token_group;
+pub use token_group::*;
+
+pub mod freeze_holder;
+pub use freeze_holder::*;
+
+pub mod mint_to;
+pub use mint_to::*;
+
+pub mod revoke_authority;
+pub use revoke_authority::*;
+
+pub mod pause;
+pub use pause::*;
+
+pub mod update_role;
+pub use update_role::*;
@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        extension::{
+            default_account_state::DefaultAccountState, BaseStateWithExtensions,
+            StateWithExtensions,
+        },
+        state::{AccountState, Mint as MintState},
+    },
+    token_interface::{
+        default_account_state_update, thaw_account, DefaultAccountStateUpdate, Mint, ThawAccount,
+        Token2022, TokenAccount,
+    },
+};
+
+use crate::{ErrorCode, FreezeRecord, PauseUpdated, ProgramState, Role};
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::Pauser, pauser.key) @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    pub pauser: Signer<'info>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct ThawAfterPause<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    /// CHECK: the token account's freeze record PDA, which is only initialized once compliance acted on it
+    #[account(seeds = [b"freeze", token_account.key().as_ref()], bump)]
+    pub freeze_record: UncheckedAccount<'info>,
+
+    #[account(
+        address = state.token_mint,
+        constraint = mint_account.freeze_authority == Some(state.key()).into() @ ErrorCode::AuthorityRevoked
+    )]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// while paused, the program's transfer, claim, staking, distribution and withdrawal instructions
+// are rejected; if the mint has the DefaultAccountState extension, new token accounts also start
+// frozen, and unpausing only resets the default, so those accounts stay frozen until thawed
+pub fn process_pause(ctx: Context<SetPaused>) -> Result<()> {
+    set_paused(ctx, true)
+}
+
+pub fn process_unpause(ctx: Context<SetPaused>) -> Result<()> {
+    set_paused(ctx, false)
+}
+
+fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    ctx.accounts.state.paused = paused;
+
+    // the default account state can only be updated while the freeze authority has not been revoked
+    if ctx.accounts.mint_account.freeze_authority.is_some()
+        && has_default_account_state(&ctx.accounts.mint_account.to_account_info())?
+    {
+        let account_state = if paused {
+            AccountState::Frozen
+        } else {
+            AccountState::Initialized
+        };
+
+        // the program state PDA is the freeze authority
+        let signer_seeds = ctx.accounts.state.signer_seeds();
+
+        default_account_state_update(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                DefaultAccountStateUpdate {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.mint_account.to_account_info(),
+                    freeze_authority: ctx.accounts.state.to_account_info(),
+                },
+                &[&signer_seeds],
+            ),
+            &account_state,
+        )?;
+    }
+
+    emit!(PauseUpdated {
+        pauser: ctx.accounts.pauser.key(),
+        paused,
+    });
+    Ok(())
+}
+
+fn has_default_account_state(mint: &AccountInfo) -> Result<bool> {
+    let mint_data = mint.data.borrow();
+    let mint_with_extension = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+    Ok(mint_with_extension
+        .get_extension::<DefaultAccountState>()
+        .is_ok())
+}
+
+// anyone can thaw a token account that was created frozen while the program was paused
+// accounts frozen by the compliance role keep their freeze record and can only be thawed by it
+pub fn process_thaw_after_pause(ctx: Context<ThawAfterPause>) -> Result<()> {
+    let freeze_record = &ctx.accounts.freeze_record;
+    if !freeze_record.data_is_empty() {
+        let record = FreezeRecord::try_deserialize(&mut &freeze_record.data.borrow()[..])?;
+        require!(!record.frozen, ErrorCode::FrozenByCompliance);
+    }
+
+    let signer_seeds = ctx.accounts.state.signer_seeds();
+
+    thaw_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        ThawAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint_account.to_account_info(),
+            authority: ctx.accounts.state.to_account_info(),
+        },
+        &[&signer_seeds],
+    ))
+}
@@ -0,0 +1,183 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use super::eligibility_rules;
+use crate::{
+    add_to_total, ErrorCode, LotteryRound, LotteryWinnerPaid, ProgramState, RewardRecipient,
+    StakePool, Stats,
+};
+
+#[derive(Accounts)]
+pub struct PayLotteryWinner<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Account<'info, Stats>,
+
+    /// CHECK: the stake pool PDA, which may not be initialized yet; stakers' unclaimed rewards stay in the vault
+    #[account(seeds = [b"stake_pool"], bump)]
+    pub stake_pool: UncheckedAccount<'info>,
+
+    /// CHECK: the eligibility rules PDA, which may not be initialized yet
+    #[account(seeds = [b"eligibility"], bump)]
+    pub eligibility: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"lottery", round.round_id.to_le_bytes().as_ref()],
+        bump = round.bump,
+        constraint = round.revealed @ ErrorCode::LotteryNotReady
+    )]
+    pub round: Account<'info, LotteryRound>,
+
+    /// The winner's associated token account of the tax token
+    #[account(
+        associated_token::mint = state.token_mint,
+        associated_token::authority = holder_token_account.owner,
+        associated_token::token_program = holder_token_account.to_account_info().owner
+    )]
+    pub holder_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the winner's reward recipient PDA, which may not exist
+    #[account(seeds = [b"reward_recipient", holder_token_account.owner.as_ref()], bump)]
+    pub reward_recipient: UncheckedAccount<'info>,
+
+    #[account(address = state.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    /// The reward vault, owned by the program state PDA
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = state,
+        associated_token::token_program = token_program
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::token_program = token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// pays draw `index` of a revealed round to the entrant whose weight range contains the drawn point
+// the entrant's leaf (owner, weight_start, weight_end) is proven against the committed root,
+// so anyone can recompute every winner from the round account and the published entrant list;
+// draws are with replacement, and winners must still pass the eligibility rules and hold at
+// least the weight their leaf claims, since the keeper's entrant list isn't verified on chain
+// the proof and eligibility checks bind the payee, so anyone can pay out a revealed draw
+pub fn process_pay_lottery_winner(
+    ctx: Context<PayLotteryWinner>,
+    index: u8,
+    weight_start: u64,
+    weight_end: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let round = &ctx.accounts.round;
+    require!(index < round.winner_count, ErrorCode::InvalidLotteryParams);
+    require!(
+        round.winners[index as usize] == Pubkey::default(),
+        ErrorCode::LotteryWinnerPaid
+    );
+
+    let winner = ctx.accounts.holder_token_account.owner;
+    let point = round.draw(index);
+    require!(
+        weight_start <= point && point < weight_end,
+        ErrorCode::InvalidLotteryProof
+    );
+    let leaf = hashv(&[
+        winner.as_ref(),
+        &weight_start.to_le_bytes(),
+        &weight_end.to_le_bytes(),
+    ])
+    .to_bytes();
+    require!(
+        verify_proof(leaf, &proof, round.entrants_root),
+        ErrorCode::InvalidLotteryProof
+    );
+
+    require!(
+        weight_end - weight_start <= ctx.accounts.holder_token_account.amount,
+        ErrorCode::InvalidLotteryProof
+    );
+
+    let rules = eligibility_rules(&ctx.accounts.eligibility)?;
+    require!(
+        rules.is_eligible(&winner, ctx.accounts.holder_token_account.amount),
+        ErrorCode::IneligibleRecipient
+    );
+
+    let reward_recipient = &ctx.accounts.reward_recipient;
+    let recipient = if reward_recipient.data_is_empty() {
+        winner
+    } else {
+        RewardRecipient::try_deserialize(&mut &reward_recipient.data.borrow()[..])?.recipient
+    };
+    require_keys_eq!(
+        ctx.accounts.destination.owner,
+        recipient,
+        ErrorCode::InvalidBatchData
+    );
+
+    let amount = round.prize_per_winner;
+    let stake_pool = &ctx.accounts.stake_pool;
+    let reserved = if stake_pool.data_is_empty() {
+        0
+    } else {
+        StakePool::try_deserialize(&mut &stake_pool.data.borrow()[..])?.unclaimed_rewards
+    };
+    require!(
+        amount <= ctx.accounts.reward_vault.amount.saturating_sub(reserved),
+        ErrorCode::InsufficientRewards
+    );
+
+    let signer_seeds = ctx.accounts.state.signer_seeds();
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.state.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+
+    let round = &mut ctx.accounts.round;
+    round.winners[index as usize] = winner;
+    add_to_total(&mut ctx.accounts.stats.total_rewards_distributed, amount)?;
+
+    emit!(LotteryWinnerPaid {
+        round_id: round.round_id,
+        index,
+        winner,
+        point,
+        amount,
+    });
+    Ok(())
+}
+
+// sorted-pair merkle proof, so the proof needs no left/right flags
+fn verify_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == root
+}
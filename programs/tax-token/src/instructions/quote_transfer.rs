@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use super::get_transfer_fee_config;
+use crate::{ErrorCode, FeeSchedule, TransferQuote};
+
+#[derive(Accounts)]
+pub struct QuoteTransfer<'info> {
+    pub mint_account: InterfaceAccount<'info, Mint>,
+}
+
+// read-only: quotes the fee for a transfer of `amount` at `epoch` (defaults to the current epoch)
+// the quote is returned via return data, so clients can get it by simulating the transaction
+pub fn process_quote_transfer(
+    ctx: Context<QuoteTransfer>,
+    amount: u64,
+    epoch: Option<u64>,
+) -> Result<TransferQuote> {
+    // read mint account extension data
+    let fee_config = get_transfer_fee_config(&ctx.accounts.mint_account.to_account_info())?;
+
+    let epoch = match epoch {
+        Some(epoch) => epoch,
+        None => Clock::get()?.epoch,
+    };
+    let fee = fee_config
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or(ErrorCode::FeeCalculationFailed)?;
+
+    // the newer fee is still pending until its activation epoch
+    let newer_transfer_fee = FeeSchedule::from(&fee_config.newer_transfer_fee);
+    let (active_fee, pending_fee) = if epoch >= newer_transfer_fee.epoch {
+        (newer_transfer_fee, None)
+    } else {
+        (
+            FeeSchedule::from(&fee_config.older_transfer_fee),
+            Some(newer_transfer_fee),
+        )
+    };
+
+    Ok(TransferQuote {
+        amount,
+        fee,
+        net_amount: amount - fee,
+        epoch,
+        active_fee,
+        pending_fee,
+    })
+}
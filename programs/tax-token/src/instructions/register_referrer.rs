@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{ErrorCode, ProgramState, Referrer};
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Referrer::LEN,
+        seeds = [b"referrer", wallet.as_ref()],
+        bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// referrers are registered by the authority, so holders can't refer their own transfers
+// through a second wallet to get part of the fee back
+pub fn process_register_referrer(ctx: Context<RegisterReferrer>, wallet: Pubkey) -> Result<()> {
+    let referrer = &mut ctx.accounts.referrer;
+    referrer.wallet = wallet;
+    referrer.claimable = 0;
+    referrer.total_earned = 0;
+    referrer.bump = ctx.bumps.referrer;
+    Ok(())
+}
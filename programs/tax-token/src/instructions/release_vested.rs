@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked_with_fee, Mint, Token2022, TokenAccount, TransferCheckedWithFee,
+};
+
+use super::{get_transfer_fee_config, gross_up};
+use crate::{ErrorCode, ProgramState, Vesting, VestingReleased};
+
+#[derive(Accounts)]
+pub struct ReleaseVested<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            beneficiary.key().as_ref(),
+            vesting.schedule_id.to_le_bytes().as_ref()
+        ],
+        bump = vesting.bump,
+        has_one = beneficiary,
+        has_one = vault
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    pub beneficiary: Signer<'info>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::authority = beneficiary,
+        token::token_program = token_program
+    )]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// releases everything vested so far to the beneficiary
+// the transfer is grossed up for the fee out of the vault's fee reserve, so the beneficiary
+// receives the vested amount; if the fee has gone up since the schedule was created the reserve
+// can run short, and the vault pays out what it has. Only what the beneficiary received counts as
+// released, so the rest stays owed until the vault is topped up
+pub fn process_release_vested(ctx: Context<ReleaseVested>) -> Result<()> {
+    let vesting = &mut ctx.accounts.vesting;
+    let due = vesting.vested_amount(Clock::get()?.unix_timestamp) - vesting.released;
+    require!(due > 0, ErrorCode::NothingToRelease);
+
+    let fee_config = get_transfer_fee_config(&ctx.accounts.mint_account.to_account_info())?;
+    let epoch = Clock::get()?.epoch;
+    let (mut amount, mut fee) = gross_up(&fee_config, epoch, due)?;
+    if amount > ctx.accounts.vault.amount {
+        amount = ctx.accounts.vault.amount;
+        fee = fee_config
+            .calculate_epoch_fee(epoch, amount)
+            .ok_or(ErrorCode::FeeCalculationFailed)?;
+    }
+    let received = amount - fee;
+    require!(received > 0, ErrorCode::VestingReserveShort);
+    vesting.released += received;
+
+    let schedule_id = vesting.schedule_id.to_le_bytes();
+    let signer_seeds = vesting.signer_seeds(&schedule_id);
+
+    transfer_checked_with_fee(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferCheckedWithFee {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                source: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint_account.to_account_info(),
+                destination: ctx.accounts.beneficiary_token_account.to_account_info(),
+                authority: vesting.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        amount,                             // transfer amount
+        ctx.accounts.mint_account.decimals, // decimals
+        fee,                                // fee
+    )?;
+
+    emit!(VestingReleased {
+        beneficiary: vesting.beneficiary,
+        schedule_id: vesting.schedule_id,
+        amount: received,
+        released: vesting.released,
+    });
+    Ok(())
+}
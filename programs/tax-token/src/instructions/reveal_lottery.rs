@@ -0,0 +1,80 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{hash::hashv, sysvar::slot_hashes},
+};
+
+use crate::{ErrorCode, LotteryFailed, LotteryRevealed, LotteryRound};
+
+#[derive(Accounts)]
+pub struct RevealLottery<'info> {
+    #[account(
+        mut,
+        seeds = [b"lottery", round.round_id.to_le_bytes().as_ref()],
+        bump = round.bump,
+        constraint = !round.revealed @ ErrorCode::LotteryAlreadyRevealed,
+        constraint = !round.failed @ ErrorCode::LotteryFailed
+    )]
+    pub round: Account<'info, LotteryRound>,
+
+    /// CHECK: the SlotHashes sysvar, parsed by hand since it is too large to deserialize
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+}
+
+// anyone can reveal a round once its target slot has passed, so the keeper can't hold back
+// an unfavourable result; the seed is derived from the committed entrants and the slot hash
+// if the target slot has already left the SlotHashes window (~512 slots), the round fails and
+// pays nothing; re-committing would let whoever controls the timing reroll an unfavourable draw
+pub fn process_reveal_lottery(ctx: Context<RevealLottery>) -> Result<()> {
+    let current_slot = Clock::get()?.slot;
+    let round = &mut ctx.accounts.round;
+    require!(current_slot > round.target_slot, ErrorCode::LotteryNotReady);
+
+    let Some(slot_hash) = find_slot_hash(&ctx.accounts.slot_hashes, round.target_slot)? else {
+        round.failed = true;
+        msg!("target slot {} expired, round failed", round.target_slot);
+        emit!(LotteryFailed {
+            round_id: round.round_id,
+            target_slot: round.target_slot,
+        });
+        return Ok(());
+    };
+
+    round.seed = hashv(&[
+        &round.round_id.to_le_bytes(),
+        &round.entrants_root,
+        &slot_hash,
+    ])
+    .to_bytes();
+    round.revealed = true;
+
+    emit!(LotteryRevealed {
+        round_id: round.round_id,
+        target_slot: round.target_slot,
+        seed: round.seed,
+    });
+    Ok(())
+}
+
+// SlotHashes data is a u64 entry count followed by (slot: u64, hash: [u8; 32]) entries,
+// newest first
+fn find_slot_hash(slot_hashes: &AccountInfo, slot: u64) -> Result<Option<[u8; 32]>> {
+    const ENTRY_LEN: usize = 8 + 32;
+
+    let data = slot_hashes.try_borrow_data()?;
+    let count = data
+        .get(..8)
+        .map(|len| u64::from_le_bytes(len.try_into().unwrap()) as usize)
+        .ok_or(ErrorCode::LotteryNotReady)?;
+
+    for entry in data[8..].chunks_exact(ENTRY_LEN).take(count) {
+        let entry_slot = u64::from_le_bytes(entry[..8].try_into().unwrap());
+        if entry_slot == slot {
+            return Ok(Some(entry[8..].try_into().unwrap()));
+        }
+        if entry_slot < slot {
+            break;
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::instruction::AuthorityType,
+    token_interface::{set_authority, Mint, SetAuthority, Token2022},
+};
+
+use crate::{ErrorCode, FreezeAuthorityRevoked, MintAuthorityRevoked, ProgramState};
+
+#[derive(Accounts)]
+pub struct RevokeAuthority<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// revoking is permanent: no more tokens can be minted past the current supply
+pub fn process_revoke_mint_authority(ctx: Context<RevokeAuthority>) -> Result<()> {
+    require!(
+        ctx.accounts.mint_account.mint_authority.is_some(),
+        ErrorCode::AuthorityRevoked
+    );
+
+    revoke(&ctx, AuthorityType::MintTokens)?;
+
+    emit!(MintAuthorityRevoked {
+        authority: ctx.accounts.authority.key(),
+        supply: ctx.accounts.mint_account.supply,
+    });
+    Ok(())
+}
+
+// revoking is permanent: token accounts can no longer be frozen, and `pause` stops updating the default account state
+pub fn process_revoke_freeze_authority(ctx: Context<RevokeAuthority>) -> Result<()> {
+    require!(
+        ctx.accounts.mint_account.freeze_authority.is_some(),
+        ErrorCode::AuthorityRevoked
+    );
+
+    revoke(&ctx, AuthorityType::FreezeAccount)?;
+
+    emit!(FreezeAuthorityRevoked {
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+fn revoke(ctx: &Context<RevokeAuthority>, authority_type: AuthorityType) -> Result<()> {
+    let signer_seeds = ctx.accounts.state.signer_seeds();
+
+    set_authority(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            SetAuthority {
+                current_authority: ctx.accounts.state.to_account_info(),
+                account_or_mint: ctx.accounts.mint_account.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        authority_type,
+        None,
+    )
+}
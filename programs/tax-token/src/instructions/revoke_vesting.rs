@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked_with_fee, Mint, Token2022, TokenAccount, TransferCheckedWithFee,
+};
+
+use super::{get_transfer_fee_config, vesting_reserve};
+use crate::{ErrorCode, ProgramState, Vesting, VestingRevoked};
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.beneficiary.as_ref(),
+            vesting.schedule_id.to_le_bytes().as_ref()
+        ],
+        bump = vesting.bump,
+        has_one = vault,
+        constraint = vesting.revocable && !vesting.revoked @ ErrorCode::VestingNotRevocable
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::authority = authority,
+        token::token_program = token_program
+    )]
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// returns the unvested amount to the authority; what has already vested stays releasable
+// the vault keeps enough to release the vested remainder grossed up for the fee, and the rest,
+// including the unvested part's fee reserve, goes back, so the fee on the return comes out of
+// that reserve
+pub fn process_revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+    let vesting = &mut ctx.accounts.vesting;
+    let vested = vesting.vested_amount(Clock::get()?.unix_timestamp);
+    let unvested = vesting.total_amount - vested;
+    vesting.total_amount = vested;
+    vesting.revoked = true;
+
+    let fee_config = get_transfer_fee_config(&ctx.accounts.mint_account.to_account_info())?;
+    let epoch = Clock::get()?.epoch;
+    let owed = vesting_reserve(&fee_config, epoch, vested - vesting.released)?;
+    let surplus = ctx.accounts.vault.amount.saturating_sub(owed);
+
+    if unvested > 0 && surplus > 0 {
+        let fee = fee_config
+            .calculate_epoch_fee(epoch, surplus)
+            .ok_or(ErrorCode::FeeCalculationFailed)?;
+
+        let schedule_id = vesting.schedule_id.to_le_bytes();
+        let signer_seeds = vesting.signer_seeds(&schedule_id);
+
+        transfer_checked_with_fee(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferCheckedWithFee {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    source: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint_account.to_account_info(),
+                    destination: ctx.accounts.authority_token_account.to_account_info(),
+                    authority: vesting.to_account_info(),
+                },
+                &[&signer_seeds],
+            ),
+            surplus,                            // transfer amount
+            ctx.accounts.mint_account.decimals, // decimals
+            fee,                                // fee
+        )?;
+    }
+
+    emit!(VestingRevoked {
+        beneficiary: vesting.beneficiary,
+        schedule_id: vesting.schedule_id,
+        unvested,
+    });
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::{RewardRecipient, RewardRecipientCleared, RewardRecipientSet};
+
+#[derive(Accounts)]
+pub struct SetRewardRecipient<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = RewardRecipient::LEN,
+        seeds = [b"reward_recipient", owner.key().as_ref()],
+        bump
+    )]
+    pub reward_recipient: Account<'info, RewardRecipient>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClearRewardRecipient<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"reward_recipient", owner.key().as_ref()],
+        bump = reward_recipient.bump,
+        has_one = owner
+    )]
+    pub reward_recipient: Account<'info, RewardRecipient>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+// holders keeping tokens in cold wallets can have their rewards paid to another wallet
+pub fn process_set_reward_recipient(
+    ctx: Context<SetRewardRecipient>,
+    recipient: Pubkey,
+) -> Result<()> {
+    let reward_recipient = &mut ctx.accounts.reward_recipient;
+    reward_recipient.owner = ctx.accounts.owner.key();
+    reward_recipient.recipient = recipient;
+    reward_recipient.bump = ctx.bumps.reward_recipient;
+
+    emit!(RewardRecipientSet {
+        owner: reward_recipient.owner,
+        recipient,
+    });
+    Ok(())
+}
+
+// closing the mapping sends rewards back to the holder's own wallet
+pub fn process_clear_reward_recipient(ctx: Context<ClearRewardRecipient>) -> Result<()> {
+    emit!(RewardRecipientCleared {
+        owner: ctx.accounts.owner.key(),
+    });
+    Ok(())
+}
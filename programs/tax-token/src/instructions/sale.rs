@@ -0,0 +1,249 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer as SystemTransfer},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+use super::mint_capped;
+use crate::{
+    add_to_total, ErrorCode, ProceedsWithdrawn, ProgramState, Purchase, Sale, SaleFinalized,
+    SaleParams, TokensPurchased,
+};
+
+#[derive(Accounts)]
+pub struct InitializeSale<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Sale::LEN,
+        seeds = [b"sale"],
+        bump
+    )]
+    pub sale: Account<'info, Sale>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        address = state.token_mint,
+        constraint = mint_account.mint_authority == Some(state.key()).into() @ ErrorCode::AuthorityRevoked
+    )]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Buy<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"sale"], bump = sale.bump)]
+    pub sale: Account<'info, Sale>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = Purchase::LEN,
+        seeds = [b"purchase", buyer.key().as_ref()],
+        bump
+    )]
+    pub purchase: Account<'info, Purchase>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        address = state.token_mint,
+        constraint = mint_account.mint_authority == Some(state.key()).into() @ ErrorCode::AuthorityRevoked
+    )]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = mint_account,
+        associated_token::authority = buyer,
+        associated_token::token_program = token_program
+    )]
+    pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeSale<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"sale"], bump = sale.bump)]
+    pub sale: Account<'info, Sale>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProceeds<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [b"sale"],
+        bump = sale.bump,
+        constraint = sale.finalized @ ErrorCode::SaleNotFinalized
+    )]
+    pub sale: Account<'info, Sale>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+}
+
+// a fixed-price sale of up to `hard_cap` newly minted tokens between `start_ts` and `end_ts`
+// the hard cap must fit under the max supply when the sale starts
+pub fn process_initialize_sale(ctx: Context<InitializeSale>, params: SaleParams) -> Result<()> {
+    require!(
+        params.price_lamports > 0
+            && params.start_ts < params.end_ts
+            && params.hard_cap > 0
+            && params.wallet_cap > 0,
+        ErrorCode::InvalidSaleParams
+    );
+    let supply = ctx
+        .accounts
+        .mint_account
+        .supply
+        .checked_add(params.hard_cap)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    require!(
+        supply <= ctx.accounts.state.max_supply,
+        ErrorCode::MaxSupplyExceeded
+    );
+
+    let sale = &mut ctx.accounts.sale;
+    sale.price_lamports = params.price_lamports;
+    sale.start_ts = params.start_ts;
+    sale.end_ts = params.end_ts;
+    sale.hard_cap = params.hard_cap;
+    sale.wallet_cap = params.wallet_cap;
+    sale.sold = 0;
+    sale.proceeds = 0;
+    sale.finalized = false;
+    sale.bump = ctx.bumps.sale;
+    Ok(())
+}
+
+// the buyer pays SOL into the sale PDA and the tokens are minted straight to their token account,
+// so purchases are exempt from the transfer fee
+pub fn process_buy(ctx: Context<Buy>, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let sale = &mut ctx.accounts.sale;
+    require!(
+        !sale.finalized && sale.start_ts <= now && now < sale.end_ts,
+        ErrorCode::SaleNotActive
+    );
+    require!(amount > 0, ErrorCode::InvalidSaleParams);
+
+    add_to_total(&mut sale.sold, amount)?;
+    require!(sale.sold <= sale.hard_cap, ErrorCode::SaleHardCapExceeded);
+
+    let purchase = &mut ctx.accounts.purchase;
+    purchase.buyer = ctx.accounts.buyer.key();
+    purchase.bump = ctx.bumps.purchase;
+    add_to_total(&mut purchase.amount, amount)?;
+    require!(
+        purchase.amount <= sale.wallet_cap,
+        ErrorCode::WalletCapExceeded
+    );
+
+    // the price is per whole token, rounded up to the next lamport
+    let unit = 10u128.pow(ctx.accounts.mint_account.decimals as u32);
+    let cost = u64::try_from((amount as u128 * sale.price_lamports as u128).div_ceil(unit))
+        .map_err(|_| ErrorCode::ArithmeticOverflow)?;
+    add_to_total(&mut sale.proceeds, cost)?;
+
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            SystemTransfer {
+                from: ctx.accounts.buyer.to_account_info(),
+                to: sale.to_account_info(),
+            },
+        ),
+        cost,
+    )?;
+
+    mint_capped(
+        &ctx.accounts.state,
+        &ctx.accounts.mint_account,
+        &ctx.accounts.buyer_token_account.to_account_info(),
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+
+    emit!(TokensPurchased {
+        buyer: ctx.accounts.buyer.key(),
+        amount,
+        cost,
+        sold: ctx.accounts.sale.sold,
+    });
+    Ok(())
+}
+
+// closes the sale once it has ended or sold out; unsold tokens are simply never minted
+pub fn process_finalize_sale(ctx: Context<FinalizeSale>) -> Result<()> {
+    let sale = &mut ctx.accounts.sale;
+    require!(!sale.finalized, ErrorCode::SaleNotActive);
+    require!(
+        Clock::get()?.unix_timestamp >= sale.end_ts || sale.sold == sale.hard_cap,
+        ErrorCode::SaleNotEnded
+    );
+    sale.finalized = true;
+
+    emit!(SaleFinalized {
+        sold: sale.sold,
+        proceeds: sale.proceeds,
+    });
+    Ok(())
+}
+
+// proceeds stay in the sale PDA until the sale is finalized
+pub fn process_withdraw_proceeds(ctx: Context<WithdrawProceeds>) -> Result<()> {
+    let sale = &mut ctx.accounts.sale;
+    let amount = sale.proceeds;
+    sale.proceeds = 0;
+
+    // the sale PDA is owned by the program, so its lamports can be moved directly
+    sale.sub_lamports(amount)?;
+    ctx.accounts.destination.add_lamports(amount)?;
+
+    emit!(ProceedsWithdrawn {
+        destination: ctx.accounts.destination.key(),
+        amount,
+    });
+    Ok(())
+}
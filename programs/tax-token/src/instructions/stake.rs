@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked_with_fee, Mint, Token2022, TokenAccount, TransferCheckedWithFee,
+};
+
+use super::get_transfer_fee_config;
+use crate::{
+    add_to_total, ErrorCode, ProgramState, StakePool, StakePosition, StakeTerm, Staked, Unstaked,
+    REWARD_PRECISION,
+};
+
+#[derive(Accounts)]
+#[instruction(term: StakeTerm)]
+pub struct Stake<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"stake_pool"], bump = stake_pool.bump)]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = StakePosition::LEN,
+        seeds = [b"stake", owner.key().as_ref(), &[term as u8]],
+        bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = stake_pool.vault)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+// tokens are locked in the stake vault for the term, earning rewards with the term's weight multiplier
+// staking more into an existing position restarts its lock
+// the transfer fee applies, so the position holds the amount received by the vault
+pub fn process_stake(ctx: Context<Stake>, term: StakeTerm, amount: u64) -> Result<()> {
+    let fee = epoch_fee(&ctx.accounts.mint_account, amount)?;
+    transfer_checked_with_fee(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferCheckedWithFee {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                source: ctx.accounts.owner_token_account.to_account_info(),
+                mint: ctx.accounts.mint_account.to_account_info(),
+                destination: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        amount,                             // transfer amount
+        ctx.accounts.mint_account.decimals, // decimals
+        fee,                                // fee
+    )?;
+    let staked = amount - fee;
+
+    let stake_pool = &mut ctx.accounts.stake_pool;
+    let position = &mut ctx.accounts.position;
+    position.owner = ctx.accounts.owner.key();
+    position.term = term;
+    position.bump = ctx.bumps.position;
+    position.accrue(stake_pool.acc_reward_per_weight)?;
+
+    let old_weight = position.weight;
+    add_to_total(&mut position.amount, staked)?;
+    position.weight = term.weight(position.amount)?;
+    position.unlock_ts = Clock::get()?.unix_timestamp + term.duration();
+    position.reward_debt =
+        position.weight as u128 * stake_pool.acc_reward_per_weight / REWARD_PRECISION;
+
+    add_to_total(&mut stake_pool.total_staked, staked)?;
+    stake_pool.total_weight = stake_pool.total_weight - old_weight + position.weight;
+
+    emit!(Staked {
+        owner: position.owner,
+        term,
+        amount: staked,
+        weight: position.weight,
+        unlock_ts: position.unlock_ts,
+    });
+    Ok(())
+}
+
+// the whole position is returned once unlocked; earned rewards stay claimable
+// the transfer fee applies, so the owner receives (amount - fee)
+pub fn process_unstake(ctx: Context<Stake>, term: StakeTerm) -> Result<()> {
+    let stake_pool = &mut ctx.accounts.stake_pool;
+    let position = &mut ctx.accounts.position;
+    require!(position.amount > 0, ErrorCode::NothingStaked);
+    require!(
+        Clock::get()?.unix_timestamp >= position.unlock_ts,
+        ErrorCode::StakeLocked
+    );
+    position.accrue(stake_pool.acc_reward_per_weight)?;
+
+    let amount = position.amount;
+    stake_pool.total_staked -= amount;
+    stake_pool.total_weight -= position.weight;
+    position.amount = 0;
+    position.weight = 0;
+    position.reward_debt = 0;
+
+    let fee = epoch_fee(&ctx.accounts.mint_account, amount)?;
+    let signer_seeds = stake_pool.signer_seeds();
+
+    transfer_checked_with_fee(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferCheckedWithFee {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                source: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint_account.to_account_info(),
+                destination: ctx.accounts.owner_token_account.to_account_info(),
+                authority: stake_pool.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        amount,                             // transfer amount
+        ctx.accounts.mint_account.decimals, // decimals
+        fee,                                // fee
+    )?;
+
+    emit!(Unstaked {
+        owner: position.owner,
+        term,
+        amount,
+    });
+    Ok(())
+}
+
+fn epoch_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let epoch = Clock::get()?.epoch;
+    get_transfer_fee_config(&mint.to_account_info())?
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or(ErrorCode::FeeCalculationFailed.into())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    ErrorCode, LotteryParams, LotteryRound, LotteryStarted, ProgramState, Role,
+    LOTTERY_REVEAL_DELAY, MAX_LOTTERY_WINNERS,
+};
+
+#[derive(Accounts)]
+#[instruction(params: LotteryParams)]
+pub struct StartLottery<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::Keeper, keeper.key) @ ErrorCode::UnauthorizedAccess,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = keeper,
+        space = LotteryRound::LEN,
+        seeds = [b"lottery", params.round_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub round: Account<'info, LotteryRound>,
+
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// commits to the entrants of a lottery round before its randomness exists
+// `entrants_root` is the merkle root of (owner, weight_start, weight_end) leaves, where each
+// holder's balance covers [weight_start, weight_end) of a line of length `total_weight`;
+// the round draws its randomness from the slot hash of `target_slot`
+pub fn process_start_lottery(ctx: Context<StartLottery>, params: LotteryParams) -> Result<()> {
+    require!(
+        params.total_weight > 0
+            && params.winner_count > 0
+            && params.winner_count as usize <= MAX_LOTTERY_WINNERS,
+        ErrorCode::InvalidLotteryParams
+    );
+
+    let round = &mut ctx.accounts.round;
+    round.round_id = params.round_id;
+    round.entrants_root = params.entrants_root;
+    round.total_weight = params.total_weight;
+    round.winner_count = params.winner_count;
+    round.prize_per_winner = params.prize_per_winner;
+    round.target_slot = Clock::get()?.slot + LOTTERY_REVEAL_DELAY;
+    round.revealed = false;
+    round.seed = [0; 32];
+    round.winners = [Pubkey::default(); MAX_LOTTERY_WINNERS];
+    round.failed = false;
+    round.bump = ctx.bumps.round;
+
+    emit!(LotteryStarted {
+        round_id: round.round_id,
+        entrants_root: round.entrants_root,
+        total_weight: round.total_weight,
+        winner_count: round.winner_count,
+        prize_per_winner: round.prize_per_winner,
+        target_slot: round.target_slot,
+    });
+    Ok(())
+}
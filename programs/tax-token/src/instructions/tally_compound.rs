@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use super::{eligibility_rules, eligible_holder};
+use crate::{
+    add_to_total, CompoundPreference, CompoundTallied, CompoundTally, ErrorCode, ProgramState,
+    Role, MAX_TRANSFER_BATCH,
+};
+
+#[derive(Accounts)]
+pub struct TallyCompound<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::Keeper, keeper.key) @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    /// CHECK: the eligibility rules PDA, which may not be initialized yet
+    #[account(seeds = [b"eligibility"], bump)]
+    pub eligibility: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [b"compound_tally"], bump = tally.bump)]
+    pub tally: Account<'info, CompoundTally>,
+
+    pub keeper: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct StartCompoundRound<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::Keeper, keeper.key) @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = CompoundTally::LEN,
+        seeds = [b"compound_tally"],
+        bump
+    )]
+    pub tally: Account<'info, CompoundTally>,
+
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// compound rounds are numbered independently of distributions, so reward batches paid between
+// a tally and its payout don't affect the round; rounds start at 1 so 0 can mean "never"
+pub fn process_start_compound_round(ctx: Context<StartCompoundRound>) -> Result<()> {
+    let tally = &mut ctx.accounts.tally;
+    add_to_total(&mut tally.round, 1)?;
+    tally.opted_in_balance = 0;
+    tally.opted_in_holders = 0;
+    tally.supply = 0;
+    tally.paid = 0;
+    tally.bump = ctx.bumps.tally;
+    Ok(())
+}
+
+// adds opted-in holders' balances to the tally of the round opened by `start_compound_round`, so the keeper knows which share
+// of the harvested tax to keep un-swapped for `distribute_compound`
+// remaining accounts are (holder token account, compound preference) pairs, followed by the
+// holder's holding record when a minimum holding age is set; the preferences must be writable
+pub fn process_tally_compound<'info>(
+    ctx: Context<'_, '_, 'info, 'info, TallyCompound<'info>>,
+) -> Result<()> {
+    let rules = eligibility_rules(&ctx.accounts.eligibility)?;
+    let stride = rules.accounts_per_recipient(2);
+    require!(
+        !ctx.remaining_accounts.is_empty(),
+        ErrorCode::EmptyTransferList
+    );
+    require!(
+        ctx.remaining_accounts.len() <= MAX_TRANSFER_BATCH * stride,
+        ErrorCode::BatchTooLarge
+    );
+    require!(
+        ctx.remaining_accounts
+            .chunks_exact(stride)
+            .remainder()
+            .is_empty(),
+        ErrorCode::InvalidBatchData
+    );
+
+    let token_mint = ctx.accounts.state.token_mint;
+    let now = Clock::get()?.unix_timestamp;
+
+    let tally = &mut ctx.accounts.tally;
+    let round = tally.round;
+    require!(round > 0, ErrorCode::NotCompounding);
+    tally.supply = ctx.accounts.mint_account.supply;
+
+    for accounts in ctx.remaining_accounts.chunks(stride) {
+        let holder_account = eligible_holder(
+            &rules,
+            &accounts[0],
+            None,
+            accounts.get(2),
+            &token_mint,
+            now,
+            ctx.program_id,
+        )?;
+
+        let mut preference = Account::<CompoundPreference>::try_from(&accounts[1])?;
+        require_keys_eq!(
+            preference.owner,
+            holder_account.owner,
+            ErrorCode::InvalidBatchData
+        );
+        require!(preference.enabled, ErrorCode::NotCompounding);
+        require!(preference.tallied_round != round, ErrorCode::AlreadyTallied);
+        preference.tallied_round = round;
+        preference.exit(ctx.program_id)?;
+
+        add_to_total(&mut tally.opted_in_balance, holder_account.amount)?;
+        tally.opted_in_holders = tally
+            .opted_in_holders
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+    }
+
+    emit!(CompoundTallied {
+        round,
+        opted_in_balance: tally.opted_in_balance,
+        opted_in_holders: tally.opted_in_holders,
+        supply: tally.supply,
+        opted_in_share_bps: tally.opted_in_share_bps(),
+    });
+    Ok(())
+}
@@ -0,0 +1,403 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+        state::Mint as MintState,
+    },
+    token_interface::{
+        token_group_initialize, token_member_initialize, Mint, Token2022, TokenGroupInitialize,
+        TokenMemberInitialize,
+    },
+};
+use spl_token_group_interface::state::{TokenGroup, TokenGroupMember};
+
+use super::{add_destination, ensure_rewards_settled};
+use crate::{
+    ErrorCode, ProgramState, Role, TokenGroupCreated, TokenGroupJoined, TokenGroupMembership,
+    TokenGroupState, TokenGroupSynced, TokenGroupUpdated, Treasury,
+};
+
+#[derive(Accounts)]
+pub struct CreateTokenGroup<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = TokenGroupState::LEN,
+        seeds = [b"token_group"],
+        bump
+    )]
+    pub group_state: Account<'info, TokenGroupState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinTokenGroup<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = TokenGroupMembership::LEN,
+        seeds = [b"token_group_member"],
+        bump
+    )]
+    pub membership: Account<'info, TokenGroupMembership>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// CHECK: the stake pool PDA, which may not be initialized yet
+    #[account(seeds = [b"stake_pool"], bump)]
+    pub stake_pool: UncheckedAccount<'info>,
+    /// CHECK: the stream pool PDA, which may not be initialized yet
+    #[account(seeds = [b"stream_pool"], bump)]
+    pub stream_pool: UncheckedAccount<'info>,
+    /// CHECK: the stream pool's vault, checked in `ensure_rewards_settled`
+    pub stream_vault: UncheckedAccount<'info>,
+    /// CHECK: the current reward mint
+    #[account(address = state.reward_mint)]
+    pub reward_mint: UncheckedAccount<'info>,
+    /// CHECK: the current reward vault, checked in `ensure_rewards_settled`
+    pub reward_vault: UncheckedAccount<'info>,
+
+    /// CHECK: the program managing the group, usually another deployment of this program
+    #[account(executable)]
+    pub group_program: UncheckedAccount<'info>,
+    /// CHECK: the group's `TokenGroupState` PDA, checked against `group_program` in the processor
+    pub group_state: UncheckedAccount<'info>,
+    /// CHECK: the group mint, checked against the group state
+    #[account(mut)]
+    pub group_mint: UncheckedAccount<'info>,
+    /// The group's update authority, which must approve every new member
+    pub group_update_authority: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTokenGroup<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_group"],
+        bump = group_state.bump,
+        has_one = update_authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub group_state: Account<'info, TokenGroupState>,
+
+    pub update_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SyncTokenGroup<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"token_group_member"], bump = membership.bump)]
+    pub membership: Account<'info, TokenGroupMembership>,
+
+    pub authority: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// CHECK: the stake pool PDA, which may not be initialized yet
+    #[account(seeds = [b"stake_pool"], bump)]
+    pub stake_pool: UncheckedAccount<'info>,
+    /// CHECK: the stream pool PDA, which may not be initialized yet
+    #[account(seeds = [b"stream_pool"], bump)]
+    pub stream_pool: UncheckedAccount<'info>,
+    /// CHECK: the stream pool's vault, checked in `ensure_rewards_settled`
+    pub stream_vault: UncheckedAccount<'info>,
+    /// CHECK: the current reward mint
+    #[account(address = state.reward_mint)]
+    pub reward_mint: UncheckedAccount<'info>,
+    /// CHECK: the current reward vault, checked in `ensure_rewards_settled`
+    pub reward_vault: UncheckedAccount<'info>,
+
+    /// CHECK: the group's `TokenGroupState` PDA, checked against the membership's group program in the processor
+    pub group_state: UncheckedAccount<'info>,
+}
+
+// makes the tax token the group of a family of tax tokens, which is stored in the mint itself
+// the group state PDA holds the settings the members share: the reward mint and the treasury their tax can be sent to
+// the authority wallet is the group's update authority, so member tokens from other deployments can be approved
+pub fn process_create_token_group(ctx: Context<CreateTokenGroup>, max_size: u32) -> Result<()> {
+    let mint = ctx.accounts.mint_account.to_account_info();
+    require!(
+        has_extension(&mint, ExtensionType::GroupPointer)?,
+        ErrorCode::MissingGroupPointer
+    );
+
+    fund_extension::<TokenGroup>(&mint, &ctx.accounts.authority, &ctx.accounts.system_program)?;
+
+    let signer_seeds = ctx.accounts.state.signer_seeds();
+    token_group_initialize(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TokenGroupInitialize {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                group: mint.clone(),
+                mint: mint.clone(),
+                mint_authority: ctx.accounts.state.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        Some(ctx.accounts.authority.key()), // Group update authority
+        max_size,
+    )?;
+
+    let group_state = &mut ctx.accounts.group_state;
+    group_state.group_mint = mint.key();
+    group_state.update_authority = ctx.accounts.authority.key();
+    group_state.reward_mint = ctx.accounts.state.reward_mint;
+    group_state.treasury = ctx.accounts.treasury.key();
+    group_state.max_size = max_size;
+    group_state.bump = ctx.bumps.group_state;
+
+    emit!(TokenGroupCreated {
+        group_mint: group_state.group_mint,
+        update_authority: group_state.update_authority,
+        reward_mint: group_state.reward_mint,
+        treasury: group_state.treasury,
+        max_size,
+    });
+    Ok(())
+}
+
+// makes the tax token a member of a group and adopts the group's shared settings:
+// rewards are paid in the group's reward mint, and the group treasury is allowlisted for `treasury_spend`
+pub fn process_join_token_group(ctx: Context<JoinTokenGroup>) -> Result<()> {
+    let group = group_settings(&ctx.accounts.group_state, &ctx.accounts.group_program.key())?;
+    require_keys_eq!(
+        group.group_mint,
+        ctx.accounts.group_mint.key(),
+        ErrorCode::InvalidTokenGroup
+    );
+
+    let mint = ctx.accounts.mint_account.to_account_info();
+    require!(
+        has_extension(&mint, ExtensionType::GroupMemberPointer)?,
+        ErrorCode::MissingGroupPointer
+    );
+
+    fund_extension::<TokenGroupMember>(
+        &mint,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+    )?;
+
+    let signer_seeds = ctx.accounts.state.signer_seeds();
+    token_member_initialize(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TokenMemberInitialize {
+            token_program_id: ctx.accounts.token_program.to_account_info(),
+            member: mint.clone(),
+            member_mint: mint.clone(),
+            member_mint_authority: ctx.accounts.state.to_account_info(),
+            group: ctx.accounts.group_mint.to_account_info(),
+            group_update_authority: ctx.accounts.group_update_authority.to_account_info(),
+        },
+        &[&signer_seeds],
+    ))?;
+
+    let member_number = {
+        let mint_data = mint.data.borrow();
+        let mint_with_extension = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+        u32::from(
+            mint_with_extension
+                .get_extension::<TokenGroupMember>()?
+                .member_number,
+        )
+    };
+
+    if group.reward_mint != ctx.accounts.state.reward_mint {
+        require!(
+            ctx.accounts
+                .state
+                .has_role(Role::RewardAdmin, ctx.accounts.authority.key),
+            ErrorCode::UnauthorizedAccess
+        );
+        ensure_rewards_settled(
+            &ctx.accounts.state,
+            &ctx.accounts.reward_mint,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.stake_pool,
+            &ctx.accounts.stream_pool,
+            &ctx.accounts.stream_vault,
+        )?;
+        ctx.accounts.state.reward_mint = group.reward_mint;
+    }
+    add_destination(&mut ctx.accounts.treasury, group.treasury)?;
+
+    let membership = &mut ctx.accounts.membership;
+    membership.group_program = ctx.accounts.group_program.key();
+    membership.group_mint = group.group_mint;
+    membership.member_number = member_number;
+    membership.treasury = group.treasury;
+    membership.bump = ctx.bumps.membership;
+
+    emit!(TokenGroupJoined {
+        group_mint: group.group_mint,
+        member_mint: mint.key(),
+        member_number,
+        reward_mint: group.reward_mint,
+    });
+    Ok(())
+}
+
+// changes the settings the group's members share; members pick them up with `sync_token_group`
+pub fn process_update_token_group(
+    ctx: Context<UpdateTokenGroup>,
+    reward_mint: Option<Pubkey>,
+    treasury: Option<Pubkey>,
+) -> Result<()> {
+    let group_state = &mut ctx.accounts.group_state;
+    if let Some(reward_mint) = reward_mint {
+        group_state.reward_mint = reward_mint;
+    }
+    if let Some(treasury) = treasury {
+        group_state.treasury = treasury;
+    }
+
+    emit!(TokenGroupUpdated {
+        group_mint: group_state.group_mint,
+        reward_mint: group_state.reward_mint,
+        treasury: group_state.treasury,
+    });
+    Ok(())
+}
+
+// re-reads the group's shared settings after a `update_token_group`: the reward mint is switched
+// once rewards are settled, and the group treasury replaces the previous one on the allowlist
+pub fn process_sync_token_group(ctx: Context<SyncTokenGroup>) -> Result<()> {
+    let group = group_settings(
+        &ctx.accounts.group_state,
+        &ctx.accounts.membership.group_program,
+    )?;
+    require_keys_eq!(
+        group.group_mint,
+        ctx.accounts.membership.group_mint,
+        ErrorCode::InvalidTokenGroup
+    );
+
+    if group.reward_mint != ctx.accounts.state.reward_mint {
+        require!(
+            ctx.accounts
+                .state
+                .has_role(Role::RewardAdmin, ctx.accounts.authority.key),
+            ErrorCode::UnauthorizedAccess
+        );
+        ensure_rewards_settled(
+            &ctx.accounts.state,
+            &ctx.accounts.reward_mint,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.stake_pool,
+            &ctx.accounts.stream_pool,
+            &ctx.accounts.stream_vault,
+        )?;
+        ctx.accounts.state.reward_mint = group.reward_mint;
+    }
+
+    let membership = &mut ctx.accounts.membership;
+    if group.treasury != membership.treasury {
+        ctx.accounts
+            .treasury
+            .allowlist
+            .retain(|allowed| *allowed != membership.treasury);
+        add_destination(&mut ctx.accounts.treasury, group.treasury)?;
+        membership.treasury = group.treasury;
+    }
+
+    emit!(TokenGroupSynced {
+        group_mint: group.group_mint,
+        member_mint: ctx.accounts.mint_account.key(),
+        reward_mint: group.reward_mint,
+        treasury: group.treasury,
+    });
+    Ok(())
+}
+
+// the group's `TokenGroupState` PDA, which must belong to `group_program`
+fn group_settings(group_state: &AccountInfo, group_program: &Pubkey) -> Result<TokenGroupState> {
+    require_keys_eq!(
+        *group_state.owner,
+        *group_program,
+        ErrorCode::InvalidTokenGroup
+    );
+    require_keys_eq!(
+        group_state.key(),
+        Pubkey::find_program_address(&[b"token_group"], group_program).0,
+        ErrorCode::InvalidTokenGroup
+    );
+    TokenGroupState::try_deserialize(&mut &group_state.data.borrow()[..])
+}
+
+fn has_extension(mint: &AccountInfo, extension_type: ExtensionType) -> Result<bool> {
+    let mint_data = mint.data.borrow();
+    let mint_with_extension = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+    Ok(mint_with_extension
+        .get_extension_types()?
+        .contains(&extension_type))
+}
+
+// the token program reallocates the mint for group data, but the extra rent has to be there beforehand
+fn fund_extension<'info, V>(
+    mint: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    // type and length header plus the extension data
+    let data_len = mint.data_len() + 4 + std::mem::size_of::<V>();
+    let lamports = Rent::get()?
+        .minimum_balance(data_len)
+        .saturating_sub(mint.lamports());
+
+    if lamports > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: mint.clone(),
+                },
+            ),
+            lamports,
+        )?;
+    }
+    Ok(())
+}
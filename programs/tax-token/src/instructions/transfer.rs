@@ -12,8 +12,17 @@ use anchor_spl::{
     },
 };
 
+use crate::{add_to_total, ErrorCode, ProgramState, ReferralConfig, ReferralCredited, Referrer};
+
 #[derive(Accounts)]
 pub struct Transfer<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
     #[account(mut)]
     pub sender: Signer<'info>,
     pub recipient: SystemAccount<'info>,
@@ -38,6 +47,17 @@ pub struct Transfer<'info> {
     pub token_program: Program<'info, Token2022>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+
+    #[account(mut, seeds = [b"referral_config"], bump = referral_config.bump)]
+    pub referral_config: Option<Account<'info, ReferralConfig>>,
+    /// The registered referrer credited with a share of the fee, if any
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.wallet.as_ref()],
+        bump = referrer.bump,
+        constraint = referrer.wallet != sender.key() @ ErrorCode::SelfReferral
+    )]
+    pub referrer: Option<Account<'info, Referrer>>,
 }
 
 // transfer fees are automatically deducted from the transfer amount
@@ -45,15 +65,70 @@ pub struct Transfer<'info> {
 // transfer fees are stored directly on the recipient token account and must be "harvested"
 pub fn process_transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
     // read mint account extension data
-    let mint = &ctx.accounts.mint_account.to_account_info();
-    let mint_data = mint.data.borrow();
-    let mint_with_extension = StateWithExtensions::<MintState>::unpack(&mint_data)?;
-    let extension_data = mint_with_extension.get_extension::<TransferFeeConfig>()?;
+    let fee_config = get_transfer_fee_config(&ctx.accounts.mint_account.to_account_info())?;
 
     // calculate expected fee
     let epoch = Clock::get()?.epoch;
-    let fee = extension_data.calculate_epoch_fee(epoch, amount).unwrap();
+    let fee = fee_config
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or(ErrorCode::FeeCalculationFailed)?;
 
+    transfer_with_fee(&ctx, amount, fee)?;
+    credit_referrer(ctx.accounts, fee)?;
+
+    msg!("transfer amount {}", amount);
+    msg!("fee amount {}", fee);
+
+    Ok(())
+}
+
+// the transfer amount is grossed up so the recipient receives exactly `net_amount`
+// the fee in effect for the current epoch (older or newer) is used, including its maximum fee cap
+pub fn process_transfer_net(ctx: Context<Transfer>, net_amount: u64) -> Result<()> {
+    // read mint account extension data
+    let fee_config = get_transfer_fee_config(&ctx.accounts.mint_account.to_account_info())?;
+
+    // calculate gross amount and fee for the current epoch
+    let epoch = Clock::get()?.epoch;
+    let (amount, fee) = gross_up(&fee_config, epoch, net_amount)?;
+
+    transfer_with_fee(&ctx, amount, fee)?;
+    credit_referrer(ctx.accounts, fee)?;
+
+    msg!("transfer amount {}", amount);
+    msg!("net amount {}", net_amount);
+    msg!("fee amount {}", fee);
+
+    Ok(())
+}
+
+// returns the (gross amount, fee) of a transfer at `epoch` that leaves exactly `net_amount` to the recipient
+pub fn gross_up(fee_config: &TransferFeeConfig, epoch: u64, net_amount: u64) -> Result<(u64, u64)> {
+    let transfer_fee = fee_config.get_epoch_fee(epoch);
+    let amount = transfer_fee
+        .calculate_pre_fee_amount(net_amount)
+        .ok_or(ErrorCode::FeeCalculationFailed)?;
+    let fee = transfer_fee
+        .calculate_fee(amount)
+        .ok_or(ErrorCode::FeeCalculationFailed)?;
+
+    // a 100% fee (or overflow) can make the requested net amount unreachable
+    require!(
+        amount.checked_sub(fee) == Some(net_amount),
+        ErrorCode::FeeCalculationFailed
+    );
+    Ok((amount, fee))
+}
+
+// reads the transfer fee extension data from the mint account
+pub fn get_transfer_fee_config(mint: &AccountInfo) -> Result<TransferFeeConfig> {
+    let mint_data = mint.data.borrow();
+    let mint_with_extension = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+    let extension_data = mint_with_extension.get_extension::<TransferFeeConfig>()?;
+    Ok(*extension_data)
+}
+
+fn transfer_with_fee(ctx: &Context<Transfer>, amount: u64, fee: u64) -> Result<()> {
     // mint account decimals
     let decimals = ctx.accounts.mint_account.decimals;
 
@@ -71,10 +146,30 @@ pub fn process_transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
         amount,   // transfer amount
         decimals, // decimals
         fee,      // fee
-    )?;
+    )
+}
 
-    msg!("transfer amount {}", amount);
-    msg!("fee amount {}", fee);
+// credits the referrer, if one was passed, with its share of the fee withheld on this transfer
+// the share is paid later from the referral vault with `claim_referral`
+fn credit_referrer(accounts: &mut Transfer, fee: u64) -> Result<()> {
+    let Some(referrer) = accounts.referrer.as_mut() else {
+        return Ok(());
+    };
+    let config = accounts
+        .referral_config
+        .as_mut()
+        .ok_or(ErrorCode::ReferralsNotEnabled)?;
+
+    let amount = (fee as u128 * config.share_bps as u128 / 10_000) as u64;
+    add_to_total(&mut referrer.claimable, amount)?;
+    add_to_total(&mut referrer.total_earned, amount)?;
+    add_to_total(&mut config.total_owed, amount)?;
 
+    emit!(ReferralCredited {
+        referrer: referrer.wallet,
+        sender: accounts.sender.key(),
+        fee,
+        amount,
+    });
     Ok(())
 }
@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{
+        create_idempotent, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_interface::{
+        transfer_checked_with_fee, Mint, Token2022, TokenAccount, TransferCheckedWithFee,
+    },
+};
+
+use super::get_transfer_fee_config;
+use crate::{ErrorCode, ProgramState, TransferManyEvent, MAX_TRANSFER_BATCH};
+
+#[derive(Accounts)]
+pub struct TransferMany<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = sender,
+        associated_token::token_program = token_program
+    )]
+    pub sender_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// same as `transfer`, but to many recipients in one instruction
+// remaining accounts are passed as (recipient wallet, recipient token account) pairs,
+// one pair per entry in `amounts`; recipient token accounts are created if needed
+pub fn process_transfer_many<'info>(
+    ctx: Context<'_, '_, 'info, 'info, TransferMany<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    require!(!amounts.is_empty(), ErrorCode::EmptyTransferList);
+    require!(
+        amounts.len() <= MAX_TRANSFER_BATCH,
+        ErrorCode::BatchTooLarge
+    );
+    require!(
+        ctx.remaining_accounts.len() == amounts.len() * 2,
+        ErrorCode::InvalidBatchData
+    );
+
+    let mint = &ctx.accounts.mint_account.to_account_info();
+    let token_program = &ctx.accounts.token_program.to_account_info();
+    let decimals = ctx.accounts.mint_account.decimals;
+    let epoch = Clock::get()?.epoch;
+
+    let mut total_amount: u64 = 0;
+    let mut total_fee: u64 = 0;
+
+    for (accounts, amount) in ctx.remaining_accounts.chunks(2).zip(amounts.iter()) {
+        let recipient = &accounts[0];
+        let recipient_token_account = &accounts[1];
+
+        // the recipient token account must be the recipient's associated token account
+        require_keys_eq!(
+            recipient_token_account.key(),
+            get_associated_token_address_with_program_id(
+                recipient.key,
+                &mint.key(),
+                &token_program.key()
+            ),
+            ErrorCode::InvalidBatchData
+        );
+
+        if recipient_token_account.data_is_empty() {
+            create_idempotent(CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                Create {
+                    payer: ctx.accounts.sender.to_account_info(),
+                    associated_token: recipient_token_account.clone(),
+                    authority: recipient.clone(),
+                    mint: mint.clone(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: token_program.clone(),
+                },
+            ))?;
+        }
+
+        // calculate expected fee for this leg
+        let fee = get_transfer_fee_config(mint)?
+            .calculate_epoch_fee(epoch, *amount)
+            .ok_or(ErrorCode::FeeCalculationFailed)?;
+
+        transfer_checked_with_fee(
+            CpiContext::new(
+                token_program.clone(),
+                TransferCheckedWithFee {
+                    token_program_id: token_program.clone(),
+                    source: ctx.accounts.sender_token_account.to_account_info(),
+                    mint: mint.clone(),
+                    destination: recipient_token_account.clone(),
+                    authority: ctx.accounts.sender.to_account_info(),
+                },
+            ),
+            *amount,  // transfer amount
+            decimals, // decimals
+            fee,      // fee
+        )?;
+
+        total_amount = total_amount
+            .checked_add(*amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        total_fee = total_fee
+            .checked_add(fee)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+    }
+
+    emit!(TransferManyEvent {
+        sender: ctx.accounts.sender.key(),
+        mint: mint.key(),
+        recipients: amounts.len() as u16,
+        total_amount,
+        total_fee,
+    });
+
+    msg!("transfer amount {}", total_amount);
+    msg!("fee amount {}", total_fee);
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked_with_fee, Mint, Token2022, TokenAccount, TransferCheckedWithFee,
+};
+
+use super::get_transfer_fee_config;
+use crate::{ErrorCode, ProgramState, Role, Treasury, TreasurySpent};
+
+#[derive(Accounts)]
+pub struct TreasurySpend<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::TreasuryAdmin, authority.key) @ ErrorCode::UnauthorizedAccess,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    pub authority: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = treasury.vault @ ErrorCode::InvalidTreasuryAccount)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program,
+        constraint = treasury.allowlist.contains(&destination.owner) @ ErrorCode::DestinationNotAllowed
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// spends from the treasury vault are limited per window and restricted to allowlisted destinations
+// the transfer fee applies, so the destination receives (amount - fee)
+pub fn process_treasury_spend(ctx: Context<TreasurySpend>, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.treasury.record_spend(amount, now)?;
+
+    // calculate expected fee
+    let epoch = Clock::get()?.epoch;
+    let fee = get_transfer_fee_config(&ctx.accounts.mint_account.to_account_info())?
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or(ErrorCode::FeeCalculationFailed)?;
+
+    let treasury = &ctx.accounts.treasury;
+    let signer_seeds = treasury.signer_seeds();
+
+    transfer_checked_with_fee(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferCheckedWithFee {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                source: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint_account.to_account_info(),
+                destination: ctx.accounts.destination.to_account_info(),
+                authority: treasury.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        amount,                             // transfer amount
+        ctx.accounts.mint_account.decimals, // decimals
+        fee,                                // fee
+    )?;
+
+    emit!(TreasurySpent {
+        destination: ctx.accounts.destination.key(),
+        amount,
+        allowance: treasury.allowance,
+        spend_limit: treasury.spend_limit,
+    });
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    Charity, CharityRegistry, ErrorCode, ProgramState, MAX_CHARITIES, MAX_CHARITY_LABEL_LEN,
+};
+
+#[derive(Accounts)]
+pub struct AddCharity<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = CharityRegistry::LEN,
+        seeds = [b"charities"],
+        bump
+    )]
+    pub charities: Account<'info, CharityRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveCharity<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"charities"], bump = charities.bump)]
+    pub charities: Account<'info, CharityRegistry>,
+
+    pub authority: Signer<'info>,
+}
+
+// registers a charity wallet, or updates its share and label if it is already registered
+// every withdrawal pays each charity `share_bps` of the withdrawn tax from the treasury vault
+pub fn process_add_charity(
+    ctx: Context<AddCharity>,
+    wallet: Pubkey,
+    share_bps: u16,
+    label: String,
+) -> Result<()> {
+    require!(
+        label.len() <= MAX_CHARITY_LABEL_LEN,
+        ErrorCode::CharityLabelTooLong
+    );
+
+    let registry = &mut ctx.accounts.charities;
+    registry.bump = ctx.bumps.charities;
+    set_charity(registry, wallet, share_bps, Some(label))
+}
+
+// upserts a charity; `label` is kept as is when `None`
+pub fn set_charity(
+    registry: &mut CharityRegistry,
+    wallet: Pubkey,
+    share_bps: u16,
+    label: Option<String>,
+) -> Result<()> {
+    match registry
+        .charities
+        .iter_mut()
+        .find(|charity| charity.wallet == wallet)
+    {
+        Some(charity) => {
+            charity.share_bps = share_bps;
+            if let Some(label) = label {
+                charity.label = label;
+            }
+        }
+        None => {
+            require!(
+                registry.charities.len() < MAX_CHARITIES,
+                ErrorCode::CharityRegistryFull
+            );
+            registry.charities.push(Charity {
+                wallet,
+                share_bps,
+                label: label.unwrap_or_default(),
+                total_donated: 0,
+            });
+        }
+    }
+
+    require!(
+        registry.total_share_bps() <= 10_000,
+        ErrorCode::CharitySharesTooHigh
+    );
+    Ok(())
+}
+
+// removing a charity drops its donation history from the registry; past donations stay in the events
+pub fn process_remove_charity(ctx: Context<RemoveCharity>, wallet: Pubkey) -> Result<()> {
+    ctx.accounts
+        .charities
+        .charities
+        .retain(|charity| charity.wallet != wallet);
+    Ok(())
+}
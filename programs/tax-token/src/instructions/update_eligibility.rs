@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{Eligibility, ErrorCode, ProgramState, MAX_EXCLUDED_OWNERS};
+
+#[derive(Accounts)]
+pub struct UpdateEligibility<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"eligibility"], bump = eligibility.bump)]
+    pub eligibility: Account<'info, Eligibility>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn process_update_eligibility(
+    ctx: Context<UpdateEligibility>,
+    min_balance: u64,
+    min_holding_seconds: i64,
+) -> Result<()> {
+    require!(min_holding_seconds >= 0, ErrorCode::InvalidHoldingPeriod);
+
+    let eligibility = &mut ctx.accounts.eligibility;
+    eligibility.min_balance = min_balance;
+    eligibility.min_holding_seconds = min_holding_seconds;
+    Ok(())
+}
+
+// excluded owners are wallets or PDAs (pool vault authorities, treasury, burn address);
+// none of their token accounts receive rewards
+pub fn process_add_excluded_owner(ctx: Context<UpdateEligibility>, owner: Pubkey) -> Result<()> {
+    let eligibility = &mut ctx.accounts.eligibility;
+    if !eligibility.excluded.contains(&owner) {
+        require!(
+            eligibility.excluded.len() < MAX_EXCLUDED_OWNERS,
+            ErrorCode::ExclusionListFull
+        );
+        eligibility.excluded.push(owner);
+    }
+    Ok(())
+}
+
+pub fn process_remove_excluded_owner(ctx: Context<UpdateEligibility>, owner: Pubkey) -> Result<()> {
+    ctx.accounts
+        .eligibility
+        .excluded
+        .retain(|excluded| *excluded != owner);
+    Ok(())
+}
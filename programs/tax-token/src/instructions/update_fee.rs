@@ -1,11 +1,20 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{transfer_fee_set, Mint, Token2022, TransferFeeSetTransferFee};
 
+use crate::{ErrorCode, ProgramState, Role};
+
 #[derive(Accounts)]
 pub struct UpdateFee<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::FeeAdmin, authority.key) @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
     pub authority: Signer<'info>,
 
-    #[account(mut)]
+    #[account(mut, address = state.token_mint)]
     pub mint_account: InterfaceAccount<'info, Mint>,
     pub token_program: Program<'info, Token2022>,
 }
@@ -18,17 +27,36 @@ pub fn process_update_fee(
     transfer_fee_basis_points: u16,
     maximum_fee: u64,
 ) -> Result<()> {
+    set_transfer_fee(
+        &ctx.accounts.state,
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_program,
+        transfer_fee_basis_points,
+        maximum_fee,
+    )
+}
+
+// the program state PDA is the transfer fee config authority
+pub fn set_transfer_fee<'info>(
+    state: &Account<'info, ProgramState>,
+    mint_account: &InterfaceAccount<'info, Mint>,
+    token_program: &Program<'info, Token2022>,
+    transfer_fee_basis_points: u16,
+    maximum_fee: u64,
+) -> Result<()> {
+    let signer_seeds = state.signer_seeds();
+
     transfer_fee_set(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
             TransferFeeSetTransferFee {
-                token_program_id: ctx.accounts.token_program.to_account_info(),
-                mint: ctx.accounts.mint_account.to_account_info(),
-                authority: ctx.accounts.authority.to_account_info(),
+                token_program_id: token_program.to_account_info(),
+                mint: mint_account.to_account_info(),
+                authority: state.to_account_info(),
             },
+            &[&signer_seeds],
         ),
         transfer_fee_basis_points, // transfer fee basis points (% fee per transfer)
         maximum_fee,               // maximum fee (maximum units of token per transfer)
-    )?;
-    Ok(())
+    )
 }
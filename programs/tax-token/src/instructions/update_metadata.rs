@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{BaseStateWithExtensions, StateWithExtensions},
+    state::Mint as MintState,
+};
+use anchor_spl::token_interface::{
+    spl_token_metadata_interface::state::{Field, TokenMetadata},
+    token_metadata_update_field, Mint, Token2022, TokenMetadataUpdateField,
+};
+
+use crate::{ErrorCode, MetadataField, ProgramState, Role};
+
+#[derive(Accounts)]
+pub struct UpdateMetadata<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::MetadataAdmin, authority.key) @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+// token metadata is stored in the mint account itself, and the program state PDA is its update authority
+// the mint account is topped up so it stays rent exempt when the metadata grows
+pub fn process_update_metadata(
+    ctx: Context<UpdateMetadata>,
+    field: MetadataField,
+    value: String,
+) -> Result<()> {
+    let field = match field {
+        MetadataField::Name => Field::Name,
+        MetadataField::Symbol => Field::Symbol,
+        MetadataField::Uri => Field::Uri,
+    };
+
+    // Calculate the mint account size after the update
+    let mint = ctx.accounts.mint_account.to_account_info();
+    let new_mint_size = {
+        let mint_data = mint.data.borrow();
+        let mint_with_extension = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+        let mut token_metadata =
+            mint_with_extension.get_variable_len_extension::<TokenMetadata>()?;
+        let old_size = token_metadata.tlv_size_of()?;
+        token_metadata.update(field.clone(), value.clone());
+        let new_size = token_metadata.tlv_size_of()?;
+        (mint.data_len() + new_size).saturating_sub(old_size)
+    };
+
+    // Transfer additional lamports to mint account for metadata
+    let additional_lamports = Rent::get()?
+        .minimum_balance(new_mint_size)
+        .saturating_sub(mint.lamports());
+    if additional_lamports > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: mint.clone(),
+                },
+            ),
+            additional_lamports,
+        )?;
+    }
+
+    let signer_seeds = ctx.accounts.state.signer_seeds();
+
+    token_metadata_update_field(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TokenMetadataUpdateField {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                metadata: mint,
+                update_authority: ctx.accounts.state.to_account_info(),
+            },
+            &[&signer_seeds],
+        ),
+        field,
+        value,
+    )?;
+    Ok(())
+}
@@ -1,31 +1,118 @@
 use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id, token_interface::TokenAccount,
+};
 
-use crate::ProgramState;
+use crate::{ErrorCode, ProgramState, Role, StakePool, StreamPool};
 
 #[derive(Accounts)]
 pub struct UpdateProgramState<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
     pub state: Account<'info, ProgramState>,
 
     pub authority: Signer<'info>,
+
+    /// CHECK: the stake pool PDA, which may not be initialized yet
+    #[account(seeds = [b"stake_pool"], bump)]
+    pub stake_pool: UncheckedAccount<'info>,
+    /// CHECK: the stream pool PDA, which may not be initialized yet
+    #[account(seeds = [b"stream_pool"], bump)]
+    pub stream_pool: UncheckedAccount<'info>,
+    /// CHECK: the stream pool's vault, checked in `ensure_rewards_settled`
+    pub stream_vault: UncheckedAccount<'info>,
+    /// CHECK: the current reward mint
+    #[account(address = state.reward_mint)]
+    pub reward_mint: UncheckedAccount<'info>,
+    /// CHECK: the current reward vault, checked in `ensure_rewards_settled`
+    pub reward_vault: UncheckedAccount<'info>,
 }
 
+// the reward mint can only be changed by the authority while it also holds the reward admin role,
+// and like a governance change only once the rewards in the current mint are settled
 pub fn process_update_program_state(
     ctx: Context<UpdateProgramState>,
     authority: Option<Pubkey>,
     reward_mint: Option<Pubkey>,
 ) -> Result<()> {
-    let state: &mut Account<'_, ProgramState> = &mut ctx.accounts.state;
-
-    assert_eq!(state.authority, ctx.accounts.authority.key());
+    if let Some(reward_mint) = reward_mint {
+        require!(
+            ctx.accounts
+                .state
+                .has_role(Role::RewardAdmin, ctx.accounts.authority.key),
+            ErrorCode::UnauthorizedAccess
+        );
+        ensure_rewards_settled(
+            &ctx.accounts.state,
+            &ctx.accounts.reward_mint,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.stake_pool,
+            &ctx.accounts.stream_pool,
+            &ctx.accounts.stream_vault,
+        )?;
+        ctx.accounts.state.reward_mint = reward_mint;
+    }
 
-    if authority.is_some() {
-        state.authority = authority.unwrap();
+    if let Some(authority) = authority {
+        ctx.accounts.state.authority = authority;
     }
 
-    if reward_mint.is_some() {
-        state.reward_mint = reward_mint.unwrap();
+    Ok(())
+}
+
+// the reward vault, staking rewards and reward streams are all held in the reward mint, so it can
+// only be switched once they are settled: the reward vault and stream vault are empty and stakers
+// have claimed everything distributed to them
+pub fn ensure_rewards_settled(
+    state: &Account<ProgramState>,
+    reward_mint: &AccountInfo,
+    reward_vault: &AccountInfo,
+    stake_pool: &AccountInfo,
+    stream_pool: &AccountInfo,
+    stream_vault: &AccountInfo,
+) -> Result<()> {
+    require_keys_eq!(
+        reward_mint.key(),
+        state.reward_mint,
+        ErrorCode::InvalidBatchData
+    );
+    require_keys_eq!(
+        reward_vault.key(),
+        get_associated_token_address_with_program_id(
+            &state.key(),
+            &state.reward_mint,
+            reward_mint.owner
+        ),
+        ErrorCode::InvalidBatchData
+    );
+    require!(
+        token_balance(reward_vault)? == 0,
+        ErrorCode::RewardsOutstanding
+    );
+
+    if !stake_pool.data_is_empty() {
+        let pool = StakePool::try_deserialize(&mut &stake_pool.data.borrow()[..])?;
+        require!(pool.unclaimed_rewards == 0, ErrorCode::RewardsOutstanding);
     }
 
+    if !stream_pool.data_is_empty() {
+        let pool = StreamPool::try_deserialize(&mut &stream_pool.data.borrow()[..])?;
+        require_keys_eq!(stream_vault.key(), pool.vault, ErrorCode::InvalidBatchData);
+        require!(
+            token_balance(stream_vault)? == 0,
+            ErrorCode::RewardsOutstanding
+        );
+    }
     Ok(())
 }
+
+fn token_balance(account: &AccountInfo) -> Result<u64> {
+    if account.data_is_empty() {
+        return Ok(0);
+    }
+    Ok(TokenAccount::try_deserialize(&mut &account.data.borrow()[..])?.amount)
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{ErrorCode, ProgramState, Role, RoleGranted, RoleRevoked};
+
+#[derive(Accounts)]
+pub struct UpdateRole<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    pub authority: Signer<'info>,
+}
+
+// roles are granted and revoked by the authority
+// each role is held by a single key; granting a role replaces its previous holder
+pub fn process_grant_role(ctx: Context<UpdateRole>, role: Role, account: Pubkey) -> Result<()> {
+    ctx.accounts.state.set_role(role, account);
+
+    emit!(RoleGranted { role, account });
+    Ok(())
+}
+
+pub fn process_revoke_role(ctx: Context<UpdateRole>, role: Role) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let account = state.role(role);
+    state.set_role(role, Pubkey::default());
+
+    emit!(RoleRevoked { role, account });
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::{ErrorCode, ProgramState, Treasury, MAX_TREASURY_DESTINATIONS};
+
+#[derive(Accounts)]
+pub struct UpdateTreasury<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    pub authority: Signer<'info>,
+}
+
+// the spend limit and allowlist are managed by the authority, not the treasury admin,
+// so a leaked treasury admin key can only spend a bounded amount to known destinations
+pub fn process_update_treasury_limit(
+    ctx: Context<UpdateTreasury>,
+    spend_limit: u64,
+    window_seconds: i64,
+) -> Result<()> {
+    require!(window_seconds > 0, ErrorCode::InvalidSpendWindow);
+
+    // the allowance refilled so far is kept, up to the new limit
+    let now = Clock::get()?.unix_timestamp;
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.allowance = treasury.available(now).min(spend_limit);
+    treasury.refilled_at = now;
+    treasury.spend_limit = spend_limit;
+    treasury.window_seconds = window_seconds;
+    Ok(())
+}
+
+// destinations are wallets; spends go to token accounts owned by them
+pub fn process_add_treasury_destination(
+    ctx: Context<UpdateTreasury>,
+    destination: Pubkey,
+) -> Result<()> {
+    add_destination(&mut ctx.accounts.treasury, destination)
+}
+
+pub fn add_destination(treasury: &mut Treasury, destination: Pubkey) -> Result<()> {
+    if !treasury.allowlist.contains(&destination) {
+        require!(
+            treasury.allowlist.len() < MAX_TREASURY_DESTINATIONS,
+            ErrorCode::AllowlistFull
+        );
+        treasury.allowlist.push(destination);
+    }
+    Ok(())
+}
+
+pub fn process_remove_treasury_destination(
+    ctx: Context<UpdateTreasury>,
+    destination: Pubkey,
+) -> Result<()> {
+    ctx.accounts
+        .treasury
+        .allowlist
+        .retain(|allowed| *allowed != destination);
+    Ok(())
+}
@@ -1,31 +1,169 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{
-    withdraw_withheld_tokens_from_mint, Mint, Token2022, TokenAccount,
-    WithdrawWithheldTokensFromMint,
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{
+        transfer_checked_with_fee, withdraw_withheld_tokens_from_mint, Mint, Token2022,
+        TokenAccount, TransferCheckedWithFee, WithdrawWithheldTokensFromMint,
+    },
+};
+
+use super::{get_transfer_fee_config, mint_withheld_amount};
+use crate::{
+    add_to_total, CharityDonated, CharityRegistry, ErrorCode, ProgramState, Role, Stats, Treasury,
 };
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::Keeper, authority.key)
+            || state.has_role(Role::TreasuryAdmin, authority.key) @ ErrorCode::UnauthorizedAccess,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Account<'info, Stats>,
+
     pub authority: Signer<'info>,
 
-    #[account(mut)]
+    #[account(mut, address = state.token_mint)]
     pub mint_account: InterfaceAccount<'info, Mint>,
-    #[account(mut)]
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, address = treasury.vault @ ErrorCode::InvalidTreasuryAccount)]
     pub token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the charity registry PDA, which may not be initialized yet
+    #[account(mut, seeds = [b"charities"], bump)]
+    pub charities: UncheckedAccount<'info>,
     pub token_program: Program<'info, Token2022>,
 }
 
 // transfer fees "harvested" to the mint account can then be withdraw by the withdraw authority
-// this transfers fees on the mint account to the specified token account
-pub fn process_withdraw(ctx: Context<Withdraw>) -> Result<()> {
-    withdraw_withheld_tokens_from_mint(CpiContext::new(
+// this transfers fees on the mint account to the treasury vault
+// the program state PDA is the withdraw authority, so either the keeper or the treasury admin can trigger it
+// registered charities are then paid their share; remaining accounts are their token accounts
+pub fn process_withdraw<'info>(ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>) -> Result<()> {
+    // everything harvested to the mint account is withdrawn
+    let withdrawn = mint_withheld_amount(&ctx.accounts.mint_account.to_account_info())?;
+    let signer_seeds = ctx.accounts.state.signer_seeds();
+
+    withdraw_withheld_tokens_from_mint(CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
         WithdrawWithheldTokensFromMint {
             token_program_id: ctx.accounts.token_program.to_account_info(),
             mint: ctx.accounts.mint_account.to_account_info(),
             destination: ctx.accounts.token_account.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
+            authority: ctx.accounts.state.to_account_info(),
         },
+        &[&signer_seeds],
     ))?;
-    Ok(())
+
+    add_to_total(&mut ctx.accounts.stats.total_tax_withdrawn, withdrawn)?;
+
+    route_donations(
+        &ctx.accounts.charities,
+        ctx.remaining_accounts,
+        withdrawn,
+        &ctx.accounts.treasury,
+        &ctx.accounts.token_account.to_account_info(),
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_program.to_account_info(),
+    )
+}
+
+// number of charity token accounts expected ahead of any other remaining accounts
+pub fn charity_count(charities: &AccountInfo) -> Result<usize> {
+    if charities.data_is_empty() {
+        return Ok(0);
+    }
+    Ok(
+        CharityRegistry::try_deserialize(&mut &charities.data.borrow()[..])?
+            .charities
+            .len(),
+    )
+}
+
+// pays each registered charity its share of `withdrawn` from the treasury vault
+// `charity_accounts` are the charities' associated token accounts, in registry order
+// charities whose account doesn't exist or is frozen are skipped, so they can't block withdrawals
+// the transfer fee applies, so each charity receives and is credited with (share - fee)
+pub fn route_donations<'info>(
+    charities: &AccountInfo<'info>,
+    charity_accounts: &[AccountInfo<'info>],
+    withdrawn: u64,
+    treasury: &Account<'info, Treasury>,
+    vault: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if charities.data_is_empty() {
+        return Ok(());
+    }
+    let mut registry = CharityRegistry::try_deserialize(&mut &charities.data.borrow()[..])?;
+    require!(
+        charity_accounts.len() == registry.charities.len(),
+        ErrorCode::InvalidBatchData
+    );
+
+    let epoch = Clock::get()?.epoch;
+    let fee_config = get_transfer_fee_config(&mint.to_account_info())?;
+    let signer_seeds = treasury.signer_seeds();
+
+    for (charity, destination) in registry.charities.iter_mut().zip(charity_accounts) {
+        require_keys_eq!(
+            destination.key(),
+            get_associated_token_address_with_program_id(
+                &charity.wallet,
+                &mint.key(),
+                token_program.key
+            ),
+            ErrorCode::InvalidBatchData
+        );
+
+        let amount = (withdrawn as u128 * charity.share_bps as u128 / 10_000) as u64;
+        if amount == 0 {
+            continue;
+        }
+        if destination.data_is_empty()
+            || TokenAccount::try_deserialize(&mut &destination.data.borrow()[..])?.is_frozen()
+        {
+            msg!("skipping donation to {}", charity.wallet);
+            continue;
+        }
+
+        // calculate expected fee for this donation
+        let fee = fee_config
+            .calculate_epoch_fee(epoch, amount)
+            .ok_or(ErrorCode::FeeCalculationFailed)?;
+
+        transfer_checked_with_fee(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TransferCheckedWithFee {
+                    token_program_id: token_program.clone(),
+                    source: vault.clone(),
+                    mint: mint.to_account_info(),
+                    destination: destination.clone(),
+                    authority: treasury.to_account_info(),
+                },
+                &[&signer_seeds],
+            ),
+            amount,        // transfer amount
+            mint.decimals, // decimals
+            fee,           // fee
+        )?;
+
+        let received = amount - fee;
+        add_to_total(&mut charity.total_donated, received)?;
+        emit!(CharityDonated {
+            wallet: charity.wallet,
+            label: charity.label.clone(),
+            amount: received,
+            total_donated: charity.total_donated,
+        });
+    }
+
+    registry.try_serialize(&mut &mut charities.data.borrow_mut()[..])
 }
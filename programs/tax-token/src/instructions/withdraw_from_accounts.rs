@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::{
+    token_2022::spl_token_2022::extension::transfer_fee::instruction::withdraw_withheld_tokens_from_accounts,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+use super::{charity_count, route_donations, token_accounts_for_mint};
+use crate::{add_to_total, ErrorCode, ProgramState, Role, Stats, Treasury};
+
+#[derive(Accounts)]
+pub struct WithdrawFromAccounts<'info> {
+    #[account(
+        seeds = [b"program_state"],
+        bump = state.bump,
+        constraint = state.has_role(Role::Keeper, authority.key)
+            || state.has_role(Role::TreasuryAdmin, authority.key) @ ErrorCode::UnauthorizedAccess,
+        constraint = !state.paused @ ErrorCode::ProgramPaused
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(mut, seeds = [b"stats"], bump = stats.bump)]
+    pub stats: Account<'info, Stats>,
+
+    pub authority: Signer<'info>,
+
+    #[account(address = state.token_mint)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    #[account(seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, address = treasury.vault @ ErrorCode::InvalidTreasuryAccount)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the charity registry PDA, which may not be initialized yet
+    #[account(mut, seeds = [b"charities"], bump)]
+    pub charities: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// transfer fees withheld on token accounts can also be withdrawn directly by the withdraw authority,
+// skipping the harvest to the mint account
+// this transfers fees on the source token accounts to the treasury vault
+// registered charities are then paid their share; their token accounts come first in the remaining accounts
+pub fn process_withdraw_from_accounts<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawFromAccounts<'info>>,
+) -> Result<()> {
+    let charities = charity_count(&ctx.accounts.charities)?;
+    require!(
+        ctx.remaining_accounts.len() >= charities,
+        ErrorCode::InvalidBatchData
+    );
+    let (charity_accounts, source_accounts) = ctx.remaining_accounts.split_at(charities);
+
+    // Using remaining accounts to allow for passing in an unknown number of token accounts to withdraw from
+    // Check that remaining accounts are token accounts for the mint to withdraw from
+    let sources = token_accounts_for_mint(source_accounts, &ctx.accounts.mint_account.key());
+
+    // the program state PDA is the withdraw authority
+    let ix = withdraw_withheld_tokens_from_accounts(
+        &ctx.accounts.token_program.key(),
+        &ctx.accounts.mint_account.key(),
+        &ctx.accounts.token_account.key(),
+        &ctx.accounts.state.key(),
+        &[],
+        &sources.iter().map(|source| source.key).collect::<Vec<_>>(),
+    )?;
+
+    let mut account_infos = vec![
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.mint_account.to_account_info(),
+        ctx.accounts.token_account.to_account_info(),
+        ctx.accounts.state.to_account_info(),
+    ];
+    account_infos.extend(sources); // token accounts to withdraw from
+
+    let balance_before = ctx.accounts.token_account.amount;
+    invoke_signed(&ix, &account_infos, &[&ctx.accounts.state.signer_seeds()])?;
+
+    ctx.accounts.token_account.reload()?;
+    let withdrawn = ctx.accounts.token_account.amount - balance_before;
+    add_to_total(&mut ctx.accounts.stats.total_tax_withdrawn, withdrawn)?;
+
+    route_donations(
+        &ctx.accounts.charities,
+        charity_accounts,
+        withdrawn,
+        &ctx.accounts.treasury,
+        &ctx.accounts.token_account.to_account_info(),
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_program.to_account_info(),
+    )
+}
@@ -1,6 +1,9 @@
 #![allow(unexpected_cfgs)]
 
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::{
+    TransferFee, MAX_FEE_BASIS_POINTS,
+};
 
 mod instructions;
 use instructions::*;
@@ -8,6 +11,20 @@ use instructions::*;
 declare_id!("C4ZgZJSwHg65gZsLoa9gt7nitzeMFRMD6eK6xMEgdyPg");
 
 const TAX_BASIS_POINT: u16 = 1000; // 10%
+const MAX_TRANSFER_BATCH: usize = 10; // recipients per `transfer_many` and `distribute`
+const MAX_TREASURY_DESTINATIONS: usize = 10; // allowlisted `treasury_spend` destinations
+const MAX_EXCLUDED_OWNERS: usize = 16; // owners excluded from rewards by `Eligibility`
+const MAX_LOTTERY_WINNERS: usize = 10; // winners per `LotteryRound`
+const LOTTERY_REVEAL_DELAY: u64 = 10; // slots between a lottery commit and its randomness slot
+const MAX_CHARITIES: usize = 5; // charities in the `CharityRegistry`
+const MAX_CHARITY_LABEL_LEN: usize = 32;
+const RAYDIUM_CLMM_PROGRAM_IDS: [Pubkey; 2] = [
+    pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK"), // mainnet
+    pubkey!("devi51mZmdwUJGU9hjN27vEz64Gps7uUefqxg27EAtH"),  // devnet
+];
+const REWARD_PRECISION: u128 = 1_000_000_000_000; // scale of `StakePool::acc_reward_per_weight`
+const SECONDS_PER_DAY: i64 = 86_400;
+const MAX_CHECKPOINT_GAP: i64 = SECONDS_PER_DAY; // longest gap between holding checkpoints before the clock restarts
 
 #[program]
 pub mod tax_token {
@@ -21,43 +38,1305 @@ pub mod tax_token {
         process_transfer(ctx, amount)
     }
 
+    pub fn transfer_net(ctx: Context<Transfer>, net_amount: u64) -> Result<()> {
+        process_transfer_net(ctx, net_amount)
+    }
+
+    pub fn transfer_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TransferMany<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        process_transfer_many(ctx, amounts)
+    }
+
+    pub fn quote_transfer(
+        ctx: Context<QuoteTransfer>,
+        amount: u64,
+        epoch: Option<u64>,
+    ) -> Result<TransferQuote> {
+        process_quote_transfer(ctx, amount, epoch)
+    }
+
     pub fn harvest<'info>(ctx: Context<'_, '_, 'info, 'info, Harvest<'info>>) -> Result<()> {
         process_harvest(ctx)
     }
 
-    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
-        process_withdraw(ctx)
+    pub fn harvest_and_close(ctx: Context<HarvestAndClose>) -> Result<()> {
+        process_harvest_and_close(ctx)
+    }
+
+    pub fn withdraw<'info>(ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>) -> Result<()> {
+        process_withdraw(ctx)
+    }
+
+    pub fn withdraw_from_accounts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawFromAccounts<'info>>,
+    ) -> Result<()> {
+        process_withdraw_from_accounts(ctx)
+    }
+
+    pub fn update_fee(
+        ctx: Context<UpdateFee>,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    ) -> Result<()> {
+        process_update_fee(ctx, transfer_fee_basis_points, maximum_fee)
+    }
+
+    pub fn update_metadata(
+        ctx: Context<UpdateMetadata>,
+        field: MetadataField,
+        value: String,
+    ) -> Result<()> {
+        process_update_metadata(ctx, field, value)
+    }
+
+    pub fn distribute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        process_distribute(ctx, amounts)
+    }
+
+    pub fn update_program_state(
+        ctx: Context<UpdateProgramState>,
+        authority: Option<Pubkey>,
+        reward_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        process_update_program_state(ctx, authority, reward_mint)
+    }
+
+    pub fn migrate_program_state(ctx: Context<MigrateProgramState>, max_supply: u64) -> Result<()> {
+        process_migrate_program_state(ctx, max_supply)
+    }
+
+    pub fn mint_to(ctx: Context<MintTo>, amount: u64) -> Result<()> {
+        process_mint_to(ctx, amount)
+    }
+
+    pub fn revoke_mint_authority(ctx: Context<RevokeAuthority>) -> Result<()> {
+        process_revoke_mint_authority(ctx)
+    }
+
+    pub fn revoke_freeze_authority(ctx: Context<RevokeAuthority>) -> Result<()> {
+        process_revoke_freeze_authority(ctx)
+    }
+
+    pub fn deposit_rewards(ctx: Context<DepositRewards>, amount: u64) -> Result<()> {
+        process_deposit_rewards(ctx, amount)
+    }
+
+    pub fn burn_tax(ctx: Context<BurnTax>, amount: u64) -> Result<()> {
+        process_burn_tax(ctx, amount)
+    }
+
+    pub fn initialize_treasury(
+        ctx: Context<InitializeTreasury>,
+        spend_limit: u64,
+        window_seconds: i64,
+    ) -> Result<()> {
+        process_initialize_treasury(ctx, spend_limit, window_seconds)
+    }
+
+    pub fn update_treasury_limit(
+        ctx: Context<UpdateTreasury>,
+        spend_limit: u64,
+        window_seconds: i64,
+    ) -> Result<()> {
+        process_update_treasury_limit(ctx, spend_limit, window_seconds)
+    }
+
+    pub fn add_treasury_destination(
+        ctx: Context<UpdateTreasury>,
+        destination: Pubkey,
+    ) -> Result<()> {
+        process_add_treasury_destination(ctx, destination)
+    }
+
+    pub fn remove_treasury_destination(
+        ctx: Context<UpdateTreasury>,
+        destination: Pubkey,
+    ) -> Result<()> {
+        process_remove_treasury_destination(ctx, destination)
+    }
+
+    pub fn treasury_spend(ctx: Context<TreasurySpend>, amount: u64) -> Result<()> {
+        process_treasury_spend(ctx, amount)
+    }
+
+    pub fn initialize_eligibility(
+        ctx: Context<InitializeEligibility>,
+        min_balance: u64,
+        min_holding_seconds: i64,
+    ) -> Result<()> {
+        process_initialize_eligibility(ctx, min_balance, min_holding_seconds)
+    }
+
+    pub fn update_eligibility(
+        ctx: Context<UpdateEligibility>,
+        min_balance: u64,
+        min_holding_seconds: i64,
+    ) -> Result<()> {
+        process_update_eligibility(ctx, min_balance, min_holding_seconds)
+    }
+
+    pub fn add_excluded_owner(ctx: Context<UpdateEligibility>, owner: Pubkey) -> Result<()> {
+        process_add_excluded_owner(ctx, owner)
+    }
+
+    pub fn remove_excluded_owner(ctx: Context<UpdateEligibility>, owner: Pubkey) -> Result<()> {
+        process_remove_excluded_owner(ctx, owner)
+    }
+
+    pub fn checkpoint_holding(ctx: Context<CheckpointHolding>) -> Result<()> {
+        process_checkpoint_holding(ctx)
+    }
+
+    pub fn set_reward_recipient(ctx: Context<SetRewardRecipient>, recipient: Pubkey) -> Result<()> {
+        process_set_reward_recipient(ctx, recipient)
+    }
+
+    pub fn clear_reward_recipient(ctx: Context<ClearRewardRecipient>) -> Result<()> {
+        process_clear_reward_recipient(ctx)
+    }
+
+    pub fn set_auto_compound(ctx: Context<SetAutoCompound>, enabled: bool) -> Result<()> {
+        process_set_auto_compound(ctx, enabled)
+    }
+
+    pub fn start_compound_round(ctx: Context<StartCompoundRound>) -> Result<()> {
+        process_start_compound_round(ctx)
+    }
+
+    pub fn tally_compound<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TallyCompound<'info>>,
+    ) -> Result<()> {
+        process_tally_compound(ctx)
+    }
+
+    pub fn distribute_compound<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeCompound<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        process_distribute_compound(ctx, amounts)
+    }
+
+    pub fn initialize_streaming(
+        ctx: Context<InitializeStreaming>,
+        period_seconds: i64,
+    ) -> Result<()> {
+        process_initialize_streaming(ctx, period_seconds)
+    }
+
+    pub fn update_stream_period(
+        ctx: Context<UpdateStreamPeriod>,
+        period_seconds: i64,
+    ) -> Result<()> {
+        process_update_stream_period(ctx, period_seconds)
+    }
+
+    pub fn distribute_streamed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeStreamed<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        process_distribute_streamed(ctx, amounts)
+    }
+
+    pub fn claim_streamed(ctx: Context<ClaimStreamed>) -> Result<()> {
+        process_claim_streamed(ctx)
+    }
+
+    pub fn initialize_referrals(ctx: Context<InitializeReferrals>, share_bps: u16) -> Result<()> {
+        process_initialize_referrals(ctx, share_bps)
+    }
+
+    pub fn update_referral_share(ctx: Context<UpdateReferralShare>, share_bps: u16) -> Result<()> {
+        process_update_referral_share(ctx, share_bps)
+    }
+
+    pub fn register_referrer(ctx: Context<RegisterReferrer>, wallet: Pubkey) -> Result<()> {
+        process_register_referrer(ctx, wallet)
+    }
+
+    pub fn claim_referral(ctx: Context<ClaimReferral>) -> Result<()> {
+        process_claim_referral(ctx)
+    }
+
+    pub fn start_lottery(ctx: Context<StartLottery>, params: LotteryParams) -> Result<()> {
+        process_start_lottery(ctx, params)
+    }
+
+    pub fn reveal_lottery(ctx: Context<RevealLottery>) -> Result<()> {
+        process_reveal_lottery(ctx)
+    }
+
+    pub fn pay_lottery_winner(
+        ctx: Context<PayLotteryWinner>,
+        index: u8,
+        weight_start: u64,
+        weight_end: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        process_pay_lottery_winner(ctx, index, weight_start, weight_end, proof)
+    }
+
+    pub fn add_charity(
+        ctx: Context<AddCharity>,
+        wallet: Pubkey,
+        share_bps: u16,
+        label: String,
+    ) -> Result<()> {
+        process_add_charity(ctx, wallet, share_bps, label)
+    }
+
+    pub fn remove_charity(ctx: Context<RemoveCharity>, wallet: Pubkey) -> Result<()> {
+        process_remove_charity(ctx, wallet)
+    }
+
+    pub fn initialize_governance(
+        ctx: Context<InitializeGovernance>,
+        params: GovernanceParams,
+    ) -> Result<()> {
+        process_initialize_governance(ctx, params)
+    }
+
+    pub fn update_governance(
+        ctx: Context<UpdateGovernance>,
+        params: GovernanceParams,
+    ) -> Result<()> {
+        process_update_governance(ctx, params)
+    }
+
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+        process_create_proposal(ctx, action)
+    }
+
+    pub fn cast_vote<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CastVote<'info>>,
+        support: bool,
+    ) -> Result<()> {
+        process_cast_vote(ctx, support)
+    }
+
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        process_execute_proposal(ctx)
+    }
+
+    pub fn sweep_forfeited_deposits(ctx: Context<SweepForfeitedDeposits>) -> Result<()> {
+        process_sweep_forfeited_deposits(ctx)
+    }
+
+    pub fn initialize_sale(ctx: Context<InitializeSale>, params: SaleParams) -> Result<()> {
+        process_initialize_sale(ctx, params)
+    }
+
+    pub fn buy(ctx: Context<Buy>, amount: u64) -> Result<()> {
+        process_buy(ctx, amount)
+    }
+
+    pub fn finalize_sale(ctx: Context<FinalizeSale>) -> Result<()> {
+        process_finalize_sale(ctx)
+    }
+
+    pub fn withdraw_proceeds(ctx: Context<WithdrawProceeds>) -> Result<()> {
+        process_withdraw_proceeds(ctx)
+    }
+
+    pub fn lock_position(ctx: Context<LockPosition>, unlock_ts: i64) -> Result<()> {
+        process_lock_position(ctx, unlock_ts)
+    }
+
+    pub fn extend_lock(ctx: Context<ExtendLock>, unlock_ts: i64) -> Result<()> {
+        process_extend_lock(ctx, unlock_ts)
+    }
+
+    pub fn unlock_position(ctx: Context<UnlockPosition>) -> Result<()> {
+        process_unlock_position(ctx)
+    }
+
+    pub fn collect_fees_while_locked<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CollectFeesWhileLocked<'info>>,
+    ) -> Result<()> {
+        process_collect_fees_while_locked(ctx)
+    }
+
+    pub fn initialize_staking(ctx: Context<InitializeStaking>) -> Result<()> {
+        process_initialize_staking(ctx)
+    }
+
+    pub fn stake(ctx: Context<Stake>, term: StakeTerm, amount: u64) -> Result<()> {
+        process_stake(ctx, term, amount)
+    }
+
+    pub fn unstake(ctx: Context<Stake>, term: StakeTerm) -> Result<()> {
+        process_unstake(ctx, term)
+    }
+
+    pub fn claim_stake_rewards(ctx: Context<ClaimStakeRewards>, term: StakeTerm) -> Result<()> {
+        process_claim_stake_rewards(ctx, term)
+    }
+
+    pub fn distribute_to_stakers(ctx: Context<DistributeToStakers>, amount: u64) -> Result<()> {
+        process_distribute_to_stakers(ctx, amount)
+    }
+
+    pub fn create_vesting(ctx: Context<CreateVesting>, params: VestingParams) -> Result<()> {
+        process_create_vesting(ctx, params)
+    }
+
+    pub fn release_vested(ctx: Context<ReleaseVested>) -> Result<()> {
+        process_release_vested(ctx)
+    }
+
+    pub fn revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+        process_revoke_vesting(ctx)
+    }
+
+    pub fn pause(ctx: Context<SetPaused>) -> Result<()> {
+        process_pause(ctx)
+    }
+
+    pub fn unpause(ctx: Context<SetPaused>) -> Result<()> {
+        process_unpause(ctx)
+    }
+
+    pub fn thaw_after_pause(ctx: Context<ThawAfterPause>) -> Result<()> {
+        process_thaw_after_pause(ctx)
+    }
+
+    pub fn configure_confidential_vault(
+        ctx: Context<ConfigureConfidentialVault>,
+        decryptable_zero_balance: [u8; 36],
+        maximum_pending_balance_credit_counter: u64,
+    ) -> Result<()> {
+        process_configure_confidential_vault(
+            ctx,
+            decryptable_zero_balance,
+            maximum_pending_balance_credit_counter,
+        )
+    }
+
+    pub fn approve_confidential_account(ctx: Context<ApproveConfidentialAccount>) -> Result<()> {
+        process_approve_confidential_account(ctx)
+    }
+
+    pub fn harvest_confidential_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, HarvestConfidentialFees<'info>>,
+    ) -> Result<()> {
+        process_harvest_confidential_fees(ctx)
+    }
+
+    pub fn withdraw_confidential_fees(
+        ctx: Context<WithdrawConfidentialFees>,
+        new_decryptable_available_balance: [u8; 36],
+    ) -> Result<()> {
+        process_withdraw_confidential_fees(ctx, new_decryptable_available_balance)
+    }
+
+    pub fn reveal_confidential_fees(
+        ctx: Context<RevealConfidentialFees>,
+        amount: u64,
+        new_decryptable_available_balance: [u8; 36],
+    ) -> Result<()> {
+        process_reveal_confidential_fees(ctx, amount, new_decryptable_available_balance)
+    }
+
+    pub fn create_token_group(ctx: Context<CreateTokenGroup>, max_size: u32) -> Result<()> {
+        process_create_token_group(ctx, max_size)
+    }
+
+    pub fn join_token_group(ctx: Context<JoinTokenGroup>) -> Result<()> {
+        process_join_token_group(ctx)
+    }
+
+    pub fn update_token_group(
+        ctx: Context<UpdateTokenGroup>,
+        reward_mint: Option<Pubkey>,
+        treasury: Option<Pubkey>,
+    ) -> Result<()> {
+        process_update_token_group(ctx, reward_mint, treasury)
+    }
+
+    pub fn sync_token_group(ctx: Context<SyncTokenGroup>) -> Result<()> {
+        process_sync_token_group(ctx)
+    }
+
+    pub fn freeze_holder(ctx: Context<FreezeHolder>, reason_code: u16) -> Result<()> {
+        process_freeze_holder(ctx, reason_code)
+    }
+
+    pub fn thaw_holder(ctx: Context<FreezeHolder>, reason_code: u16) -> Result<()> {
+        process_thaw_holder(ctx, reason_code)
+    }
+
+    pub fn grant_role(ctx: Context<UpdateRole>, role: Role, account: Pubkey) -> Result<()> {
+        process_grant_role(ctx, role, account)
+    }
+
+    pub fn revoke_role(ctx: Context<UpdateRole>, role: Role) -> Result<()> {
+        process_revoke_role(ctx, role)
+    }
+}
+
+#[account]
+pub struct ProgramState {
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub fee_admin: Pubkey,
+    pub treasury_admin: Pubkey,
+    pub metadata_admin: Pubkey,
+    pub keeper: Pubkey,
+    pub pauser: Pubkey,
+    pub compliance: Pubkey,
+    pub reward_admin: Pubkey,
+    pub paused: bool,
+    pub max_supply: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Stats {
+    pub total_tax_harvested: u64,
+    pub total_tax_withdrawn: u64,
+    pub total_tax_burned: u64,
+    pub total_rewards_deposited: u64,
+    pub total_rewards_distributed: u64,
+    pub distribution_batches: u64,
+    pub last_distribution_ts: i64,
+    pub bump: u8,
+}
+
+impl Stats {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // total_tax_harvested
+        8 + // total_tax_withdrawn
+        8 + // total_tax_burned
+        8 + // total_rewards_deposited
+        8 + // total_rewards_distributed
+        8 + // distribution_batches
+        8 + // last_distribution_ts
+        1; // bump
+}
+
+#[account]
+pub struct Treasury {
+    pub vault: Pubkey,
+    pub spend_limit: u64,
+    pub window_seconds: i64,
+    pub allowance: u64,
+    pub refilled_at: i64,
+    pub allowlist: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl Treasury {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // vault
+        8 + // spend_limit
+        8 + // window_seconds
+        8 + // allowance
+        8 + // refilled_at
+        4 + 32 * MAX_TREASURY_DESTINATIONS + // allowlist
+        1; // bump
+
+    pub fn signer_seeds(&self) -> [&[u8]; 2] {
+        [b"treasury", std::slice::from_ref(&self.bump)]
+    }
+
+    // the allowance refills linearly at `spend_limit` per `window_seconds` and never exceeds
+    // `spend_limit`, so spends can't be bunched around a window boundary to get past the limit
+    pub fn available(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.refilled_at).max(0) as u128;
+        let refill = self.spend_limit as u128 * elapsed / self.window_seconds as u128;
+        (self.allowance as u128 + refill).min(self.spend_limit as u128) as u64
+    }
+
+    pub fn record_spend(&mut self, amount: u64, now: i64) -> Result<()> {
+        let available = self.available(now);
+        require!(amount <= available, ErrorCode::SpendLimitExceeded);
+
+        self.allowance = available - amount;
+        self.refilled_at = now;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct StakePool {
+    pub vault: Pubkey,
+    pub total_staked: u64,
+    pub total_weight: u64,
+    pub acc_reward_per_weight: u128,
+    pub unclaimed_rewards: u64,
+    pub bump: u8,
+}
+
+impl StakePool {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // vault
+        8 + // total_staked
+        8 + // total_weight
+        16 + // acc_reward_per_weight
+        8 + // unclaimed_rewards
+        1; // bump
+
+    pub fn signer_seeds(&self) -> [&[u8]; 2] {
+        [b"stake_pool", std::slice::from_ref(&self.bump)]
+    }
+}
+
+#[account]
+pub struct PositionLock {
+    pub owner: Pubkey,
+    pub position_mint: Pubkey,
+    pub escrow: Pubkey,
+    pub unlock_ts: i64,
+    pub bump: u8,
+}
+
+impl PositionLock {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        32 + // position_mint
+        32 + // escrow
+        8 + // unlock_ts
+        1; // bump
+
+    pub fn signer_seeds(&self) -> [&[u8]; 3] {
+        [
+            b"position_lock",
+            self.position_mint.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
+
+#[account]
+pub struct TokenGroupState {
+    pub group_mint: Pubkey,
+    pub update_authority: Pubkey,
+    pub reward_mint: Pubkey,
+    pub treasury: Pubkey,
+    pub max_size: u32,
+    pub bump: u8,
+}
+
+impl TokenGroupState {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // group_mint
+        32 + // update_authority
+        32 + // reward_mint
+        32 + // treasury
+        4 + // max_size
+        1; // bump
+}
+
+#[account]
+pub struct TokenGroupMembership {
+    pub group_program: Pubkey,
+    pub group_mint: Pubkey,
+    pub member_number: u32,
+    pub treasury: Pubkey,
+    pub bump: u8,
+}
+
+impl TokenGroupMembership {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // group_program
+        32 + // group_mint
+        4 + // member_number
+        32 + // treasury
+        1; // bump
+}
+
+#[account]
+pub struct FreezeRecord {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub frozen: bool,
+    pub reason_code: u16,
+    pub officer: Pubkey,
+    pub updated_ts: i64,
+    pub freeze_count: u32,
+    pub action_count: u32,
+    pub bump: u8,
+}
+
+impl FreezeRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // token_account
+        32 + // owner
+        1 + // frozen
+        2 + // reason_code
+        32 + // officer
+        8 + // updated_ts
+        4 + // freeze_count
+        4 + // action_count
+        1; // bump
+}
+
+#[account]
+pub struct FreezeAction {
+    pub token_account: Pubkey,
+    pub index: u32,
+    pub frozen: bool,
+    pub reason_code: u16,
+    pub officer: Pubkey,
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
+impl FreezeAction {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // token_account
+        4 + // index
+        1 + // frozen
+        2 + // reason_code
+        32 + // officer
+        8 + // timestamp
+        1; // bump
+}
+
+#[account]
+pub struct Sale {
+    pub price_lamports: u64,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub hard_cap: u64,
+    pub wallet_cap: u64,
+    pub sold: u64,
+    pub proceeds: u64,
+    pub finalized: bool,
+    pub bump: u8,
+}
+
+impl Sale {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // price_lamports
+        8 + // start_ts
+        8 + // end_ts
+        8 + // hard_cap
+        8 + // wallet_cap
+        8 + // sold
+        8 + // proceeds
+        1 + // finalized
+        1; // bump
+}
+
+#[account]
+pub struct Purchase {
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl Purchase {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // buyer
+        8 + // amount
+        1; // bump
+}
+
+#[account]
+pub struct Governance {
+    pub vault: Pubkey,
+    pub proposal_deposit: u64,
+    pub voting_period: i64,
+    pub quorum: u64,
+    pub proposal_count: u64,
+    pub forfeited: u64,
+    pub bump: u8,
+}
+
+impl Governance {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // vault
+        8 + // proposal_deposit
+        8 + // voting_period
+        8 + // quorum
+        8 + // proposal_count
+        8 + // forfeited
+        1; // bump
+
+    pub fn signer_seeds(&self) -> [&[u8]; 2] {
+        [b"governance", std::slice::from_ref(&self.bump)]
+    }
+}
+
+#[account]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    pub deposit: u64,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub voting_ends_ts: i64,
+    pub status: ProposalStatus,
+    pub bump: u8,
+}
+
+impl Proposal {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // id
+        32 + // proposer
+        1 + 34 + // action (largest variant: SetCharityShare)
+        8 + // deposit
+        8 + // yes_votes
+        8 + // no_votes
+        8 + // voting_ends_ts
+        1 + // status
+        1; // bump
+}
+
+#[account]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u64,
+    pub bump: u8,
+}
+
+impl VoteRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // proposal
+        32 + // voter
+        1 + // support
+        8 + // weight
+        1; // bump
+}
+
+#[account]
+pub struct CharityRegistry {
+    pub charities: Vec<Charity>,
+    pub bump: u8,
+}
+
+impl CharityRegistry {
+    pub const LEN: usize = 8 + // discriminator
+        4 + Charity::LEN * MAX_CHARITIES + // charities
+        1; // bump
+
+    pub fn total_share_bps(&self) -> u32 {
+        self.charities
+            .iter()
+            .map(|charity| charity.share_bps as u32)
+            .sum()
+    }
+}
+
+#[account]
+pub struct LotteryRound {
+    pub round_id: u64,
+    pub entrants_root: [u8; 32],
+    pub total_weight: u64,
+    pub winner_count: u8,
+    pub prize_per_winner: u64,
+    pub target_slot: u64,
+    pub revealed: bool,
+    pub seed: [u8; 32],
+    pub winners: [Pubkey; MAX_LOTTERY_WINNERS],
+    pub failed: bool,
+    pub bump: u8,
+}
+
+impl LotteryRound {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // round_id
+        32 + // entrants_root
+        8 + // total_weight
+        1 + // winner_count
+        8 + // prize_per_winner
+        8 + // target_slot
+        1 + // revealed
+        32 + // seed
+        32 * MAX_LOTTERY_WINNERS + // winners
+        1 + // failed
+        1; // bump
+
+    // the winning point of draw `index` on the cumulative weight line [0, total_weight)
+    pub fn draw(&self, index: u8) -> u64 {
+        let hash = hashv(&[&self.seed, &[index]]).to_bytes();
+        let mut point = [0u8; 8];
+        point.copy_from_slice(&hash[..8]);
+        u64::from_le_bytes(point) % self.total_weight
+    }
+}
+
+#[account]
+pub struct ReferralConfig {
+    pub vault: Pubkey,
+    pub share_bps: u16,
+    pub total_owed: u64,
+    pub bump: u8,
+}
+
+impl ReferralConfig {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // vault
+        2 + // share_bps
+        8 + // total_owed
+        1; // bump
+
+    pub fn signer_seeds(&self) -> [&[u8]; 2] {
+        [b"referral_config", std::slice::from_ref(&self.bump)]
+    }
+}
+
+#[account]
+pub struct Referrer {
+    pub wallet: Pubkey,
+    pub claimable: u64,
+    pub total_earned: u64,
+    pub bump: u8,
+}
+
+impl Referrer {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // wallet
+        8 + // claimable
+        8 + // total_earned
+        1; // bump
+}
+
+#[account]
+pub struct StreamPool {
+    pub vault: Pubkey,
+    pub period_seconds: i64,
+    pub bump: u8,
+}
+
+impl StreamPool {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // vault
+        8 + // period_seconds
+        1; // bump
+
+    pub fn signer_seeds(&self) -> [&[u8]; 2] {
+        [b"stream_pool", std::slice::from_ref(&self.bump)]
+    }
+}
+
+#[account]
+pub struct RewardStream {
+    pub owner: Pubkey,
+    pub locked: u64,
+    pub unlocked: u64,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub bump: u8,
+}
+
+impl RewardStream {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        8 + // locked
+        8 + // unlocked
+        8 + // start_ts
+        8 + // end_ts
+        1; // bump
+
+    // moves the part of `locked` that has streamed since `start_ts` into `unlocked`
+    pub fn settle(&mut self, now: i64) -> Result<()> {
+        if self.locked == 0 || now <= self.start_ts {
+            return Ok(());
+        }
+        let released = if now >= self.end_ts {
+            self.locked
+        } else {
+            let elapsed = (now - self.start_ts) as u128;
+            let remaining = (self.end_ts - self.start_ts) as u128;
+            (self.locked as u128 * elapsed / remaining) as u64
+        };
+        self.locked -= released;
+        add_to_total(&mut self.unlocked, released)?;
+        self.start_ts = now;
+        Ok(())
+    }
+
+    // new rewards stream over `period`; the stream's end moves to the amount-weighted average of the
+    // time left on what is still locked and the new period, so earlier rewards aren't pushed back a full period
+    pub fn credit(&mut self, amount: u64, now: i64, period: i64) -> Result<()> {
+        self.settle(now)?;
+        let remaining = self.end_ts.saturating_sub(now).max(0) as u128;
+        let locked = self.locked as u128;
+        let total = locked + amount as u128;
+        let duration = (locked * remaining + amount as u128 * period as u128)
+            .checked_div(total)
+            .unwrap_or(period as u128);
+        add_to_total(&mut self.locked, amount)?;
+        self.start_ts = now;
+        self.end_ts = now.saturating_add(duration as i64);
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct Eligibility {
+    pub min_balance: u64,
+    pub min_holding_seconds: i64,
+    pub excluded: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl Eligibility {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // min_balance
+        8 + // min_holding_seconds
+        4 + 32 * MAX_EXCLUDED_OWNERS + // excluded
+        1; // bump
+
+    pub fn is_eligible(&self, owner: &Pubkey, balance: u64) -> bool {
+        balance > 0 && balance >= self.min_balance && !self.excluded.contains(owner)
+    }
+
+    // a holding record follows the `accounts` per recipient when a minimum holding age is set
+    pub fn accounts_per_recipient(&self, accounts: usize) -> usize {
+        if self.min_holding_seconds > 0 {
+            accounts + 1
+        } else {
+            accounts
+        }
+    }
+}
+
+#[account]
+pub struct CompoundPreference {
+    pub owner: Pubkey,
+    pub enabled: bool,
+    pub tallied_round: u64,
+    pub paid_round: u64,
+    pub bump: u8,
+}
+
+impl CompoundPreference {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        1 + // enabled
+        8 + // tallied_round
+        8 + // paid_round
+        1; // bump
+}
+
+#[account]
+pub struct CompoundTally {
+    pub round: u64,
+    pub opted_in_balance: u64,
+    pub opted_in_holders: u32,
+    pub supply: u64,
+    pub paid: u64,
+    pub bump: u8,
+}
+
+impl CompoundTally {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // round
+        8 + // opted_in_balance
+        4 + // opted_in_holders
+        8 + // supply
+        8 + // paid
+        1; // bump
+
+    // share of the supply held by compounding holders; the keeper swaps only the rest
+    pub fn opted_in_share_bps(&self) -> u64 {
+        if self.supply == 0 {
+            return 0;
+        }
+        (self.opted_in_balance as u128 * 10_000 / self.supply as u128) as u64
+    }
+}
+
+#[account]
+pub struct RewardRecipient {
+    pub owner: Pubkey,
+    pub recipient: Pubkey,
+    pub bump: u8,
+}
+
+impl RewardRecipient {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        32 + // recipient
+        1; // bump
+}
+
+#[account]
+pub struct HoldingRecord {
+    pub owner: Pubkey,
+    pub since: i64,
+    pub checked_at: i64,
+    pub bump: u8,
+}
+
+impl HoldingRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        8 + // since
+        8 + // checked_at
+        1; // bump
+
+    // the balance is only seen at checkpoints, so the clock only runs while they keep coming
+    pub fn is_continuous(&self, now: i64) -> bool {
+        now.saturating_sub(self.checked_at) <= MAX_CHECKPOINT_GAP
+    }
+}
+
+#[account]
+pub struct StakePosition {
+    pub owner: Pubkey,
+    pub term: StakeTerm,
+    pub amount: u64,
+    pub weight: u64,
+    pub unlock_ts: i64,
+    pub reward_debt: u128,
+    pub pending_rewards: u64,
+    pub bump: u8,
+}
+
+impl StakePosition {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        1 + // term
+        8 + // amount
+        8 + // weight
+        8 + // unlock_ts
+        16 + // reward_debt
+        8 + // pending_rewards
+        1; // bump
+
+    // moves rewards earned since the last update into `pending_rewards`
+    pub fn accrue(&mut self, acc_reward_per_weight: u128) -> Result<()> {
+        let earned = (self.weight as u128)
+            .checked_mul(acc_reward_per_weight)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            / REWARD_PRECISION;
+        let pending = u64::try_from(earned.saturating_sub(self.reward_debt))
+            .map_err(|_| ErrorCode::ArithmeticOverflow)?;
+        add_to_total(&mut self.pending_rewards, pending)?;
+        self.reward_debt = earned;
+        Ok(())
     }
+}
 
-    pub fn update_fee(
-        ctx: Context<UpdateFee>,
-        transfer_fee_basis_points: u16,
-        maximum_fee: u64,
-    ) -> Result<()> {
-        process_update_fee(ctx, transfer_fee_basis_points, maximum_fee)
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StakeTerm {
+    Days30,
+    Days90,
+    Days180,
+}
+
+impl StakeTerm {
+    pub fn duration(&self) -> i64 {
+        match self {
+            StakeTerm::Days30 => 30 * SECONDS_PER_DAY,
+            StakeTerm::Days90 => 90 * SECONDS_PER_DAY,
+            StakeTerm::Days180 => 180 * SECONDS_PER_DAY,
+        }
     }
 
-    pub fn update_program_state(
-        ctx: Context<UpdateProgramState>,
-        authority: Option<Pubkey>,
-        reward_mint: Option<Pubkey>,
-    ) -> Result<()> {
-        process_update_program_state(ctx, authority, reward_mint)
+    // reward weight multiplier in basis points
+    pub fn multiplier_bps(&self) -> u64 {
+        match self {
+            StakeTerm::Days30 => 12_500,  // 1.25x
+            StakeTerm::Days90 => 15_000,  // 1.5x
+            StakeTerm::Days180 => 20_000, // 2x
+        }
+    }
+
+    pub fn weight(&self, amount: u64) -> Result<u64> {
+        u64::try_from(amount as u128 * self.multiplier_bps() as u128 / 10_000)
+            .map_err(|_| ErrorCode::ArithmeticOverflow.into())
     }
 }
 
 #[account]
-pub struct ProgramState {
-    pub authority: Pubkey,
-    pub token_mint: Pubkey,
-    pub reward_mint: Pubkey,
+pub struct Vesting {
+    pub beneficiary: Pubkey,
+    pub schedule_id: u64,
+    pub vault: Pubkey,
+    pub total_amount: u64,
+    pub released: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+    pub revocable: bool,
+    pub revoked: bool,
+    pub bump: u8,
+}
+
+impl Vesting {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // beneficiary
+        8 + // schedule_id
+        32 + // vault
+        8 + // total_amount
+        8 + // released
+        8 + // start_ts
+        8 + // cliff_ts
+        8 + // end_ts
+        1 + // revocable
+        1 + // revoked
+        1; // bump
+
+    // `schedule_id` is `self.schedule_id.to_le_bytes()`, which has to outlive the seeds
+    pub fn signer_seeds<'a>(&'a self, schedule_id: &'a [u8; 8]) -> [&'a [u8]; 4] {
+        [
+            b"vesting",
+            self.beneficiary.as_ref(),
+            schedule_id,
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+
+    // nothing vests before the cliff, then linearly from start to end
+    // a revoked schedule is cut down to what had vested at the time
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        if self.revoked || now >= self.end_ts {
+            self.total_amount
+        } else if now < self.cliff_ts {
+            0
+        } else {
+            let elapsed = (now - self.start_ts) as u128;
+            let duration = (self.end_ts - self.start_ts) as u128;
+            (self.total_amount as u128 * elapsed / duration) as u64
+        }
+    }
+}
+
+pub fn add_to_total(total: &mut u64, amount: u64) -> Result<()> {
+    *total = total
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    Ok(())
 }
 
 impl ProgramState {
     pub const LEN: usize = 8 + // discriminator
         32 + // authority
         32 + // token_mint
-        32; // reward_mint
+        32 + // reward_mint
+        32 + // fee_admin
+        32 + // treasury_admin
+        32 + // metadata_admin
+        32 + // keeper
+        32 + // pauser
+        32 + // compliance
+        32 + // reward_admin
+        1 + // paused
+        8 + // max_supply
+        1; // bump
+
+    // the original layout: discriminator, authority, token_mint and reward_mint
+    pub const LEGACY_LEN: usize = 8 + 32 + 32 + 32;
+
+    pub fn role(&self, role: Role) -> Pubkey {
+        match role {
+            Role::FeeAdmin => self.fee_admin,
+            Role::TreasuryAdmin => self.treasury_admin,
+            Role::MetadataAdmin => self.metadata_admin,
+            Role::Keeper => self.keeper,
+            Role::Pauser => self.pauser,
+            Role::Compliance => self.compliance,
+            Role::RewardAdmin => self.reward_admin,
+        }
+    }
+
+    pub fn set_role(&mut self, role: Role, account: Pubkey) {
+        match role {
+            Role::FeeAdmin => self.fee_admin = account,
+            Role::TreasuryAdmin => self.treasury_admin = account,
+            Role::MetadataAdmin => self.metadata_admin = account,
+            Role::Keeper => self.keeper = account,
+            Role::Pauser => self.pauser = account,
+            Role::Compliance => self.compliance = account,
+            Role::RewardAdmin => self.reward_admin = account,
+        }
+    }
+
+    // revoked roles are set to the default pubkey, which can never sign
+    pub fn has_role(&self, role: Role, account: &Pubkey) -> bool {
+        self.role(role) == *account && *account != Pubkey::default()
+    }
+
+    pub fn signer_seeds(&self) -> [&[u8]; 2] {
+        [b"program_state", std::slice::from_ref(&self.bump)]
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum ProposalAction {
+    UpdateFee {
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    },
+    SetRewardMint {
+        reward_mint: Pubkey,
+    },
+    SetCharityShare {
+        wallet: Pubkey,
+        share_bps: u16,
+    },
+}
+
+impl ProposalAction {
+    // rejects actions that could never be applied, so they can't be put to a vote
+    pub fn validate(&self) -> Result<()> {
+        let valid = match self {
+            ProposalAction::UpdateFee {
+                transfer_fee_basis_points,
+                ..
+            } => *transfer_fee_basis_points <= MAX_FEE_BASIS_POINTS,
+            ProposalAction::SetRewardMint { reward_mint } => *reward_mint != Pubkey::default(),
+            ProposalAction::SetCharityShare { share_bps, .. } => *share_bps <= 10_000,
+        };
+        require!(valid, ErrorCode::InvalidProposalAction);
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalStatus {
+    Voting,
+    Executed,
+    Rejected,
+    Failed,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct SaleParams {
+    pub price_lamports: u64, // per whole token (10^decimals base units)
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub hard_cap: u64,
+    pub wallet_cap: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct GovernanceParams {
+    pub proposal_deposit: u64,
+    pub voting_period: i64,
+    pub quorum: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    FeeAdmin,
+    TreasuryAdmin,
+    MetadataAdmin,
+    Keeper,
+    Pauser,
+    Compliance,
+    RewardAdmin,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub enum MetadataField {
+    Name,
+    Symbol,
+    Uri,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
@@ -67,6 +1346,421 @@ pub struct InitTokenParams {
     pub uri: String,
     pub decimals: u8,
     pub total_supply: u128,
+    pub default_account_state: bool,
+    pub confidential_transfer: Option<ConfidentialTransferParams>,
+    pub group_pointer: bool,
+    pub group_member_pointer: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ConfidentialTransferParams {
+    pub auto_approve_new_accounts: bool,
+    pub auditor_elgamal_pubkey: Option<[u8; 32]>,
+    pub withdraw_withheld_authority_elgamal_pubkey: [u8; 32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct Charity {
+    pub wallet: Pubkey,
+    pub share_bps: u16,
+    pub label: String,
+    pub total_donated: u64,
+}
+
+impl Charity {
+    pub const LEN: usize = 32 + // wallet
+        2 + // share_bps
+        4 + MAX_CHARITY_LABEL_LEN + // label
+        8; // total_donated
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct LotteryParams {
+    pub round_id: u64,
+    pub entrants_root: [u8; 32],
+    pub total_weight: u64,
+    pub winner_count: u8,
+    pub prize_per_winner: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct VestingParams {
+    pub beneficiary: Pubkey,
+    pub schedule_id: u64,
+    pub amount: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+    pub revocable: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct FeeSchedule {
+    pub epoch: u64,
+    pub transfer_fee_basis_points: u16,
+    pub maximum_fee: u64,
+}
+
+impl From<&TransferFee> for FeeSchedule {
+    fn from(transfer_fee: &TransferFee) -> Self {
+        Self {
+            epoch: transfer_fee.epoch.into(),
+            transfer_fee_basis_points: transfer_fee.transfer_fee_basis_points.into(),
+            maximum_fee: transfer_fee.maximum_fee.into(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct TransferQuote {
+    pub amount: u64,
+    pub fee: u64,
+    pub net_amount: u64,
+    pub epoch: u64,
+    pub active_fee: FeeSchedule,
+    pub pending_fee: Option<FeeSchedule>,
+}
+
+#[event]
+pub struct TransferManyEvent {
+    pub sender: Pubkey,
+    pub mint: Pubkey,
+    pub recipients: u16,
+    pub total_amount: u64,
+    pub total_fee: u64,
+}
+
+#[event]
+pub struct DistributeEvent {
+    pub keeper: Pubkey,
+    pub reward_mint: Pubkey,
+    pub recipients: u16,
+    pub total_amount: u64,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub role: Role,
+    pub account: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub account: Pubkey,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub pauser: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct ConfidentialAccountApproved {
+    pub officer: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct ConfidentialFeesWithdrawn {
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+}
+
+#[event]
+pub struct ConfidentialFeesRevealed {
+    pub treasury_admin: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TokenGroupCreated {
+    pub group_mint: Pubkey,
+    pub update_authority: Pubkey,
+    pub reward_mint: Pubkey,
+    pub treasury: Pubkey,
+    pub max_size: u32,
+}
+
+#[event]
+pub struct TokenGroupJoined {
+    pub group_mint: Pubkey,
+    pub member_mint: Pubkey,
+    pub member_number: u32,
+    pub reward_mint: Pubkey,
+}
+
+#[event]
+pub struct TokenGroupUpdated {
+    pub group_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct TokenGroupSynced {
+    pub group_mint: Pubkey,
+    pub member_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct HolderFreezeUpdated {
+    pub token_account: Pubkey,
+    pub index: u32,
+    pub owner: Pubkey,
+    pub officer: Pubkey,
+    pub frozen: bool,
+    pub reason_code: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensMinted {
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub supply: u64,
+    pub max_supply: u64,
+}
+
+#[event]
+pub struct MintAuthorityRevoked {
+    pub authority: Pubkey,
+    pub supply: u64,
+}
+
+#[event]
+pub struct FreezeAuthorityRevoked {
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct TreasurySpent {
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub allowance: u64,
+    pub spend_limit: u64,
+}
+
+#[event]
+pub struct Staked {
+    pub owner: Pubkey,
+    pub term: StakeTerm,
+    pub amount: u64,
+    pub weight: u64,
+    pub unlock_ts: i64,
+}
+
+#[event]
+pub struct Unstaked {
+    pub owner: Pubkey,
+    pub term: StakeTerm,
+    pub amount: u64,
+}
+
+#[event]
+pub struct StakeRewardsDistributed {
+    pub amount: u64,
+    pub total_weight: u64,
+    pub acc_reward_per_weight: u128,
+}
+
+#[event]
+pub struct VestingCreated {
+    pub beneficiary: Pubkey,
+    pub schedule_id: u64,
+    pub amount: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+    pub revocable: bool,
+}
+
+#[event]
+pub struct VestingReleased {
+    pub beneficiary: Pubkey,
+    pub schedule_id: u64,
+    pub amount: u64,
+    pub released: u64,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub beneficiary: Pubkey,
+    pub schedule_id: u64,
+    pub unvested: u64,
+}
+
+#[event]
+pub struct RewardRecipientSet {
+    pub owner: Pubkey,
+    pub recipient: Pubkey,
+}
+
+#[event]
+pub struct RewardRecipientCleared {
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct AutoCompoundUpdated {
+    pub owner: Pubkey,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct CompoundTallied {
+    pub round: u64,
+    pub opted_in_balance: u64,
+    pub opted_in_holders: u32,
+    pub supply: u64,
+    pub opted_in_share_bps: u64,
+}
+
+#[event]
+pub struct CompoundDistributed {
+    pub keeper: Pubkey,
+    pub round: u64,
+    pub recipients: u16,
+    pub total_amount: u64,
+}
+
+#[event]
+pub struct StreamedRewardsDistributed {
+    pub keeper: Pubkey,
+    pub recipients: u16,
+    pub total_amount: u64,
+    pub period_seconds: i64,
+}
+
+#[event]
+pub struct StreamedRewardsClaimed {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub locked: u64,
+}
+
+#[event]
+pub struct ReferralCredited {
+    pub referrer: Pubkey,
+    pub sender: Pubkey,
+    pub fee: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralClaimed {
+    pub referrer: Pubkey,
+    pub amount: u64,
+    pub claimable: u64,
+}
+
+#[event]
+pub struct LotteryStarted {
+    pub round_id: u64,
+    pub entrants_root: [u8; 32],
+    pub total_weight: u64,
+    pub winner_count: u8,
+    pub prize_per_winner: u64,
+    pub target_slot: u64,
+}
+
+#[event]
+pub struct LotteryRevealed {
+    pub round_id: u64,
+    pub target_slot: u64,
+    pub seed: [u8; 32],
+}
+
+#[event]
+pub struct LotteryFailed {
+    pub round_id: u64,
+    pub target_slot: u64,
+}
+
+#[event]
+pub struct LotteryWinnerPaid {
+    pub round_id: u64,
+    pub index: u8,
+    pub winner: Pubkey,
+    pub point: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CharityDonated {
+    pub wallet: Pubkey,
+    pub label: String,
+    pub amount: u64,
+    pub total_donated: u64,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    pub deposit: u64,
+    pub voting_ends_ts: i64,
+}
+
+#[event]
+pub struct VoteCast {
+    pub id: u64,
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u64,
+}
+
+#[event]
+pub struct ProposalFinalized {
+    pub id: u64,
+    pub status: ProposalStatus,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+}
+
+#[event]
+pub struct TokensPurchased {
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub cost: u64,
+    pub sold: u64,
+}
+
+#[event]
+pub struct SaleFinalized {
+    pub sold: u64,
+    pub proceeds: u64,
+}
+
+#[event]
+pub struct ProceedsWithdrawn {
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PositionLocked {
+    pub owner: Pubkey,
+    pub position_mint: Pubkey,
+    pub unlock_ts: i64,
+}
+
+#[event]
+pub struct PositionUnlocked {
+    pub owner: Pubkey,
+    pub position_mint: Pubkey,
+}
+
+#[event]
+pub struct LockedFeesCollected {
+    pub position_mint: Pubkey,
+    pub amount_0: u64,
+    pub amount_1: u64,
 }
 
 #[error_code]
@@ -100,4 +1794,175 @@ pub enum ErrorCode {
 
     #[msg("Arithmetic overflow")]
     ArithmeticOverflow,
+
+    #[msg("Transfer fee calculation failed")]
+    FeeCalculationFailed,
+
+    #[msg("Token account must be empty to be closed")]
+    TokenAccountNotEmpty,
+
+    #[msg("Withdrawals must go to the treasury vault")]
+    InvalidTreasuryAccount,
+
+    #[msg("Program is paused")]
+    ProgramPaused,
+
+    #[msg("Minting would exceed the maximum supply")]
+    MaxSupplyExceeded,
+
+    #[msg("Authority has already been revoked")]
+    AuthorityRevoked,
+
+    #[msg("Treasury spend limit exceeded for the current window")]
+    SpendLimitExceeded,
+
+    #[msg("Destination is not allowlisted for treasury spending")]
+    DestinationNotAllowed,
+
+    #[msg("Treasury destination allowlist is full")]
+    AllowlistFull,
+
+    #[msg("Treasury spend window must be positive")]
+    InvalidSpendWindow,
+
+    #[msg("Stake is still locked")]
+    StakeLocked,
+
+    #[msg("Nothing staked")]
+    NothingStaked,
+
+    #[msg("Invalid vesting schedule")]
+    InvalidVestingSchedule,
+
+    #[msg("Nothing to release")]
+    NothingToRelease,
+
+    #[msg("Vesting is not revocable")]
+    VestingNotRevocable,
+
+    #[msg("Excluded owner list is full")]
+    ExclusionListFull,
+
+    #[msg("Invalid minimum holding period")]
+    InvalidHoldingPeriod,
+
+    #[msg("Recipient is not eligible for rewards")]
+    IneligibleRecipient,
+
+    #[msg("Holder has not opted in to auto-compounding")]
+    NotCompounding,
+
+    #[msg("Holder was already tallied or paid this round")]
+    AlreadyTallied,
+
+    #[msg("Reward stream period must be positive")]
+    InvalidStreamPeriod,
+
+    #[msg("Referral share must be at most 10000 basis points")]
+    InvalidReferralShare,
+
+    #[msg("Referrals are not enabled")]
+    ReferralsNotEnabled,
+
+    #[msg("Senders cannot refer their own transfers")]
+    SelfReferral,
+
+    #[msg("Invalid lottery parameters")]
+    InvalidLotteryParams,
+
+    #[msg("Lottery randomness is not available yet")]
+    LotteryNotReady,
+
+    #[msg("Lottery was already revealed")]
+    LotteryAlreadyRevealed,
+
+    #[msg("Lottery entry does not match the drawn point or the entrants root")]
+    InvalidLotteryProof,
+
+    #[msg("Lottery winner was already paid")]
+    LotteryWinnerPaid,
+
+    #[msg("Lottery round failed, its randomness slot expired before the reveal")]
+    LotteryFailed,
+
+    #[msg("Charity registry is full")]
+    CharityRegistryFull,
+
+    #[msg("Charity label is too long")]
+    CharityLabelTooLong,
+
+    #[msg("Charity shares exceed 10000 basis points")]
+    CharitySharesTooHigh,
+
+    #[msg("Invalid governance parameters")]
+    InvalidGovernanceParams,
+
+    #[msg("Voting on this proposal has ended")]
+    VotingClosed,
+
+    #[msg("Voting on this proposal has not ended yet")]
+    VotingNotEnded,
+
+    #[msg("Proposal was already finalized")]
+    ProposalFinalized,
+
+    #[msg("Proposal action cannot be applied")]
+    InvalidProposalAction,
+
+    #[msg("Invalid sale parameters")]
+    InvalidSaleParams,
+
+    #[msg("Sale is not active")]
+    SaleNotActive,
+
+    #[msg("Sale hard cap exceeded")]
+    SaleHardCapExceeded,
+
+    #[msg("Per-wallet purchase cap exceeded")]
+    WalletCapExceeded,
+
+    #[msg("Sale has not ended yet")]
+    SaleNotEnded,
+
+    #[msg("Sale is not finalized")]
+    SaleNotFinalized,
+
+    #[msg("Unlock time must be later than the current one")]
+    InvalidUnlockTime,
+
+    #[msg("Position is still locked")]
+    PositionLocked,
+
+    #[msg("Unknown CLMM program")]
+    InvalidClmmProgram,
+
+    #[msg("Not a CLMM position NFT")]
+    InvalidPosition,
+
+    #[msg("Reward vaults, streams or staking rewards are not settled")]
+    RewardsOutstanding,
+
+    #[msg("Holder is paid through auto-compounding")]
+    HolderCompounds,
+
+    #[msg("A non-zero reason code is required")]
+    MissingReasonCode,
+
+    #[msg("Mint was not initialized with the required group pointer")]
+    MissingGroupPointer,
+
+    #[msg("Invalid token group")]
+    InvalidTokenGroup,
+
+    #[msg("Farm rewards must be paid to the treasury")]
+    InvalidRewardRecipient,
+
+    #[msg("Program state is already in the current layout")]
+    AlreadyMigrated,
+
+    #[msg("Vesting vault can't cover the release")]
+    VestingReserveShort,
+
+    #[msg("Token account was frozen by the compliance role")]
+    FrozenByCompliance,
 }
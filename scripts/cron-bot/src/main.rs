@@ -26,6 +26,9 @@ use tokio;
 mod utils;
 use utils::{get_discriminant, get_token_accounts, setup_logging};
 
+/// Recipients per `distribute` and `distribute_streamed` call, the program's batch limit
+const DISTRIBUTE_BATCH: usize = 10;
+
 /// Main entry point for the token tax and distribution cron bot
 #[tokio::main]
 async fn main() {
@@ -148,6 +151,17 @@ async fn process_job(
         &ata_program_id,
     );
 
+    // Withdrawn taxes land in the program's treasury vault
+    let (treasury, _) = Pubkey::find_program_address(&[b"treasury"], &tax_program_id);
+    let (treasury_vault, _) = Pubkey::find_program_address(
+        &[
+            treasury.as_ref(),
+            token_2022_program_id.as_ref(),
+            token_mint.as_ref(),
+        ],
+        &ata_program_id,
+    );
+
     let rpc_client =
         RpcClient::new_with_commitment(sol_rpc_endpoint.to_string(), CommitmentConfig::confirmed());
 
@@ -192,10 +206,38 @@ async fn process_job(
         &token_2022_program_id,
         &payer,
         &payer.pubkey(),
-        &admin_ata,
+        &treasury_vault,
     )
     .await?;
 
+    let treasury_balance = rpc_client
+        .get_token_account_balance(&treasury_vault)
+        .await?
+        .amount
+        .parse::<u64>()?;
+    let spend_allowance = treasury_allowance(&rpc_client, &tax_program, &treasury).await?;
+    let spend_amount = treasury_balance.min(spend_allowance);
+    if spend_amount < treasury_balance {
+        warn!(
+            "⚠️ Treasury spend limit reached, leaving {} in the treasury",
+            treasury_balance - spend_amount
+        );
+    }
+    if spend_amount > 0 {
+        info!("🏦 Spending {} from treasury to admin ATA...", spend_amount);
+        treasury_spend(
+            &tax_program,
+            &token_mint,
+            &token_2022_program_id,
+            &payer,
+            &payer.pubkey(),
+            &treasury_vault,
+            &admin_ata,
+            spend_amount,
+        )
+        .await?;
+    }
+
     let post_harvested_balance = rpc_client
         .get_token_account_balance(&admin_ata)
         .await?
@@ -280,13 +322,14 @@ async fn process_job(
     distribute_rewards(
         rpc_client,
         client,
+        tax_program_id,
         &token_mint,
         &reward_token_mint,
-        reward_balance,
         &payer,
         token_program_id,
         token_2022_program_id,
         ata_program_id,
+        system_program_id,
     )
     .await?;
 
@@ -323,9 +366,13 @@ async fn harvest(
         .collect();
 
     debug!("📝 Building harvest transaction...");
+    let (state, _) = Pubkey::find_program_address(&[b"program_state"], &program.id());
+    let (stats, _) = Pubkey::find_program_address(&[b"stats"], &program.id());
     let tx_hash = program
         .request()
         .accounts(tax_token::accounts::Harvest {
+            state,
+            stats,
             mint_account: *mint_account,
             token_program: *token_2022_program_id,
         })
@@ -339,7 +386,7 @@ async fn harvest(
     Ok(tx_hash)
 }
 
-/// Withdraws harvested taxes to the admin's associated token account (ATA)
+/// Withdraws harvested taxes to the program's treasury vault
 ///
 /// # Arguments
 /// * `program` - Tax program instance
@@ -347,24 +394,53 @@ async fn harvest(
 /// * `token_2022_program_id` - Token 2022 program ID
 /// * `keypair` - Signer's keypair
 /// * `authority` - Authority pubkey
-/// * `authority_ata` - Authority's ATA pubkey
+/// * `treasury_vault` - Treasury vault pubkey
 async fn withdraw(
     program: &Program<Arc<Keypair>>,
     mint_account: &Pubkey,
     token_2022_program_id: &Pubkey,
     keypair: &Keypair,
     authority: &Pubkey,
-    authority_ata: &Pubkey,
+    treasury_vault: &Pubkey,
 ) -> Result<Signature, anyhow::Error> {
     info!("💸 Initiating withdrawal...");
+    let (state, _) = Pubkey::find_program_address(&[b"program_state"], &program.id());
+    let (stats, _) = Pubkey::find_program_address(&[b"stats"], &program.id());
+    let (treasury, _) = Pubkey::find_program_address(&[b"treasury"], &program.id());
+    let (charities, _) = Pubkey::find_program_address(&[b"charities"], &program.id());
+
+    // registered charities are paid their share of the withdrawal to their ATAs
+    let charity_accounts: Vec<AccountMeta> = program
+        .account::<tax_token::CharityRegistry>(charities)
+        .await
+        .map(|registry| registry.charities)
+        .unwrap_or_default()
+        .iter()
+        .map(|charity| {
+            AccountMeta::new(
+                spl_associated_token_account::get_associated_token_address_with_program_id(
+                    &charity.wallet,
+                    mint_account,
+                    token_2022_program_id,
+                ),
+                false,
+            )
+        })
+        .collect();
+
     let tx_hash = program
         .request()
         .accounts(tax_token::accounts::Withdraw {
+            state,
+            stats,
             authority: *authority,
             mint_account: *mint_account,
-            token_account: *authority_ata,
+            treasury,
+            token_account: *treasury_vault,
+            charities,
             token_program: *token_2022_program_id,
         })
+        .accounts(charity_accounts)
         .args(tax_token::instruction::Withdraw)
         .signer(keypair)
         .send()
@@ -374,6 +450,70 @@ async fn withdraw(
     Ok(tx_hash)
 }
 
+/// Returns how much the treasury's allowance has refilled to, using the cluster time so it matches
+/// the program's view of it
+///
+/// # Arguments
+/// * `rpc_client` - Solana RPC client
+/// * `program` - Tax program instance
+/// * `treasury` - Treasury PDA
+async fn treasury_allowance(
+    rpc_client: &RpcClient,
+    program: &Program<Arc<Keypair>>,
+    treasury: &Pubkey,
+) -> Result<u64, anyhow::Error> {
+    let treasury = program.account::<tax_token::Treasury>(*treasury).await?;
+    let now = rpc_client
+        .get_block_time(rpc_client.get_slot().await?)
+        .await?;
+
+    Ok(treasury.available(now))
+}
+
+/// Spends from the program's treasury vault to an allowlisted token account
+///
+/// # Arguments
+/// * `program` - Tax program instance
+/// * `mint_account` - Token mint address
+/// * `token_2022_program_id` - Token 2022 program ID
+/// * `keypair` - Signer's keypair
+/// * `authority` - Treasury admin pubkey
+/// * `treasury_vault` - Treasury vault pubkey
+/// * `destination` - Destination token account pubkey
+/// * `amount` - Amount to spend
+async fn treasury_spend(
+    program: &Program<Arc<Keypair>>,
+    mint_account: &Pubkey,
+    token_2022_program_id: &Pubkey,
+    keypair: &Keypair,
+    authority: &Pubkey,
+    treasury_vault: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) -> Result<Signature, anyhow::Error> {
+    info!("🏦 Initiating treasury spend...");
+    let (state, _) = Pubkey::find_program_address(&[b"program_state"], &program.id());
+    let (treasury, _) = Pubkey::find_program_address(&[b"treasury"], &program.id());
+    let tx_hash = program
+        .request()
+        .accounts(tax_token::accounts::TreasurySpend {
+            state,
+            treasury,
+            authority: *authority,
+            mint_account: *mint_account,
+            vault: *treasury_vault,
+            destination: *destination,
+            token_program: *token_2022_program_id,
+        })
+        .args(tax_token::instruction::TreasurySpend { amount })
+        .signer(keypair)
+        .send()
+        .await?;
+
+    info!("✅ Treasury spend completed with tx: {}", tx_hash);
+    Ok(tx_hash)
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 #[borsh(crate = "borsh")]
 pub struct SwapV2 {
@@ -498,34 +638,104 @@ async fn swap_clmm(
     }
 }
 
-/// Distributes rewards to token holders proportionally
+/// Deposits the swapped rewards into the program's reward vault and pays them to holders through
+/// `distribute`, or `distribute_streamed` once streaming is initialized, so the program's
+/// eligibility rules, reward recipients and compound preferences apply to every payout
 ///
 /// # Arguments
 /// * `rpc_client` - Solana RPC client
 /// * `client` - Anchor client instance
+/// * `tax_program_id` - ID of the tax program
 /// * `tax_token_mint` - Taxed token mint
 /// * `reward_token_mint` - Reward token mint
-/// * `total_rewards` - Total reward amount to distribute
-/// * `payer` - Transaction signer
+/// * `payer` - Transaction signer, which must hold the `Keeper` role
 /// * `[...]` - Program IDs
 async fn distribute_rewards(
     rpc_client: RpcClient,
     client: Client<Arc<Keypair>>,
+    tax_program_id: Pubkey,
     tax_token_mint: &Pubkey,
     reward_token_mint: &Pubkey,
-    total_rewards: u64,
     payer: &Keypair,
     token_program_id: Pubkey,
-    _token_2022_program_id: Pubkey,
+    token_2022_program_id: Pubkey,
     ata_program_id: Pubkey,
+    system_program_id: Pubkey,
 ) -> Result<(), anyhow::Error> {
+    let program = client.program(tax_program_id)?;
+    let (state, _) = Pubkey::find_program_address(&[b"program_state"], &tax_program_id);
+    let (stats, _) = Pubkey::find_program_address(&[b"stats"], &tax_program_id);
+    let (stake_pool, _) = Pubkey::find_program_address(&[b"stake_pool"], &tax_program_id);
+    let (eligibility, _) = Pubkey::find_program_address(&[b"eligibility"], &tax_program_id);
+    let (stream_pool, _) = Pubkey::find_program_address(&[b"stream_pool"], &tax_program_id);
+    let ata = |owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey| {
+        Pubkey::find_program_address(
+            &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+            &ata_program_id,
+        )
+        .0
+    };
+    let admin_reward_ata = ata(&payer.pubkey(), reward_token_mint, &token_program_id);
+    let reward_vault = ata(&state, reward_token_mint, &token_program_id);
+
+    let swapped = rpc_client
+        .get_token_account_balance(&admin_reward_ata)
+        .await?
+        .amount
+        .parse::<u64>()?;
+    if swapped > 0 {
+        info!("🏦 Depositing {} rewards into the reward vault...", swapped);
+        program
+            .request()
+            .accounts(tax_token::accounts::DepositRewards {
+                state,
+                stats,
+                depositor: payer.pubkey(),
+                reward_mint: *reward_token_mint,
+                depositor_token_account: admin_reward_ata,
+                reward_vault,
+                token_program: token_program_id,
+            })
+            .args(tax_token::instruction::DepositRewards { amount: swapped })
+            .signer(payer)
+            .send()
+            .await?;
+    }
+
+    // stakers' unclaimed rewards stay in the vault
+    let staking = program
+        .account::<tax_token::StakePool>(stake_pool)
+        .await
+        .ok();
+    let reserved = staking
+        .as_ref()
+        .map(|pool| pool.unclaimed_rewards)
+        .unwrap_or(0);
+    let total_rewards = rpc_client
+        .get_token_account_balance(&reward_vault)
+        .await?
+        .amount
+        .parse::<u64>()?
+        .saturating_sub(reserved);
     info!(
         "🎁 Starting reward distribution of {} tokens...",
         total_rewards
     );
-    let mint_info = rpc_client.get_token_supply(tax_token_mint).await?;
-    let reward_info = rpc_client.get_token_supply(reward_token_mint).await?;
-    let total_supply = mint_info.ui_amount.unwrap_or(0.0) as u64;
+    if total_rewards == 0 {
+        return Ok(());
+    }
+
+    let rules = program
+        .account::<tax_token::Eligibility>(eligibility)
+        .await
+        .unwrap_or_default();
+    let streams = program
+        .account::<tax_token::StreamPool>(stream_pool)
+        .await
+        .ok();
+    let now = rpc_client
+        .get_block_time(rpc_client.get_slot().await?)
+        .await?;
 
     debug!("📋 Fetching token accounts for distribution...");
     let accounts =
@@ -535,80 +745,234 @@ async fn distribute_rewards(
         return Err(anyhow!("Failed to get holders for harvesting"));
     }
     let accounts = accounts.unwrap();
-    let mut distribution_data = Vec::new();
-    for (_, (balance, wallet)) in accounts {
-        if balance > 0.0 {
-            let reward = (balance as u128 * total_rewards as u128 / total_supply as u128) as u64
-                * 10u64.pow(reward_info.decimals.into());
-            if reward > 0 {
-                distribution_data.push((wallet, reward));
-            }
+
+    // holders the program would reject are left out here, so one of them can't fail a whole batch
+    let mut holders = Vec::new();
+    for (holder_ata, (balance, wallet)) in accounts {
+        let owner = Pubkey::from_str(&wallet)?;
+        let holder_ata = Pubkey::from_str(&holder_ata)?;
+        let balance = (balance * 1_000_000_000.0).round() as u64;
+        // the stake vault is paid through `distribute_to_stakers` below
+        if owner == stake_pool || !rules.is_eligible(&owner, balance) {
+            continue;
         }
-    }
 
-    let (admin_reward_ata, _) = Pubkey::find_program_address(
-        &[
-            payer.pubkey().as_ref(),
-            token_program_id.as_ref(),
-            reward_token_mint.as_ref(),
-        ],
-        &ata_program_id,
-    );
-    let program = client.program(token_program_id)?;
-
-    info!("📤 Distributing to {} holders...", distribution_data.len());
-    for (owner, reward) in distribution_data.iter() {
-        let owner = Pubkey::from_str(owner)?;
-        let (holder_ata, _) = Pubkey::find_program_address(
-            &[
-                owner.as_ref(),
-                token_program_id.as_ref(),
-                reward_token_mint.as_ref(),
-            ],
-            &ata_program_id,
-        );
+        let (compound, _) =
+            Pubkey::find_program_address(&[b"compound", owner.as_ref()], &tax_program_id);
+        let compounds = program
+            .account::<tax_token::CompoundPreference>(compound)
+            .await
+            .map(|preference| preference.enabled)
+            .unwrap_or(false);
+        if compounds {
+            debug!("🔁 {} auto-compounds, skipping", owner);
+            continue;
+        }
 
-        if rpc_client.get_account(&holder_ata).await.is_err() {
-            debug!("🆕 Creating ATA for holder {}", owner);
-            let ix = spl_associated_token_account::instruction::create_associated_token_account(
-                &payer.pubkey(),
+        let (holding, _) =
+            Pubkey::find_program_address(&[b"holding", owner.as_ref()], &tax_program_id);
+        if rules.min_holding_seconds > 0 {
+            checkpoint_holding(
+                &program,
+                tax_token_mint,
                 &owner,
-                reward_token_mint,
-                &token_program_id,
-            );
-            program
-                .request()
-                .instruction(ix)
-                .signer(payer)
-                .send()
-                .await?;
+                &holder_ata,
+                &token_2022_program_id,
+                &system_program_id,
+                payer,
+            )
+            .await?;
+            let record = program.account::<tax_token::HoldingRecord>(holding).await?;
+            if record.since.saturating_add(rules.min_holding_seconds) > now {
+                debug!("⏳ {} hasn't held long enough, skipping", owner);
+                continue;
+            }
         }
+        holders.push((owner, holder_ata, compound, holding, balance));
+    }
 
-        debug!("💸 Transferring {} rewards to {}", reward, owner);
-        let ix = spl_token::instruction::transfer(
-            &token_program_id,
-            &admin_reward_ata,
-            &holder_ata,
-            &payer.pubkey(),
-            &[&payer.pubkey()],
-            *reward,
-        )?;
-        program
+    // staked tokens left the holders' accounts, so stakers get their share of the rewards through
+    // `distribute_to_stakers`, which splits it by boosted stake weight
+    let total_balance: u128 = holders.iter().map(|holder| holder.4 as u128).sum();
+    let total_staked = staking
+        .as_ref()
+        .filter(|pool| pool.total_weight > 0)
+        .map(|pool| pool.total_staked as u128)
+        .unwrap_or(0);
+    if total_balance + total_staked == 0 {
+        warn!("⚠️ No eligible holders or stakers, skipping distribution");
+        return Ok(());
+    }
+    let staker_rewards =
+        (total_rewards as u128 * total_staked / (total_balance + total_staked)) as u64;
+    if staker_rewards > 0 {
+        info!("🥩 Distributing {} rewards to stakers...", staker_rewards);
+        let tx_hash = program
             .request()
-            .instruction(ix)
+            .accounts(tax_token::accounts::DistributeToStakers {
+                state,
+                stats,
+                stake_pool,
+                keeper: payer.pubkey(),
+                reward_mint: *reward_token_mint,
+                reward_vault,
+                token_program: token_program_id,
+            })
+            .args(tax_token::instruction::DistributeToStakers {
+                amount: staker_rewards,
+            })
             .signer(payer)
             .send()
             .await?;
+        debug!("✅ Distributed staker rewards with tx: {}", tx_hash);
+    }
+    let total_rewards = total_rewards - staker_rewards;
+    if total_balance == 0 {
+        warn!("⚠️ No eligible holders, skipping holder distribution");
+        return Ok(());
+    }
+
+    info!("📤 Distributing to {} holders...", holders.len());
+    for batch in holders.chunks(DISTRIBUTE_BATCH) {
+        let mut amounts = Vec::new();
+        let mut remaining_accounts = Vec::new();
+        for (owner, holder_ata, compound, holding, balance) in batch {
+            let reward = (*balance as u128 * total_rewards as u128 / total_balance) as u64;
+            amounts.push(reward);
+            remaining_accounts.push(AccountMeta::new_readonly(*holder_ata, false));
+
+            if streams.is_some() {
+                let (stream, _) =
+                    Pubkey::find_program_address(&[b"stream", owner.as_ref()], &tax_program_id);
+                remaining_accounts.push(AccountMeta::new(stream, false));
+            } else {
+                // rewards go to the holder's reward recipient when they set one
+                let (reward_recipient, _) = Pubkey::find_program_address(
+                    &[b"reward_recipient", owner.as_ref()],
+                    &tax_program_id,
+                );
+                let recipient = program
+                    .account::<tax_token::RewardRecipient>(reward_recipient)
+                    .await
+                    .map(|reward_recipient| reward_recipient.recipient)
+                    .unwrap_or(*owner);
+                let destination = ata(&recipient, reward_token_mint, &token_program_id);
+                if rpc_client.get_account(&destination).await.is_err() {
+                    debug!("🆕 Creating reward ATA for {}", recipient);
+                    let ix =
+                        spl_associated_token_account::instruction::create_associated_token_account(
+                            &payer.pubkey(),
+                            &recipient,
+                            reward_token_mint,
+                            &token_program_id,
+                        );
+                    program
+                        .request()
+                        .instruction(ix)
+                        .signer(payer)
+                        .send()
+                        .await?;
+                }
+                remaining_accounts.push(AccountMeta::new(destination, false));
+                remaining_accounts.push(AccountMeta::new_readonly(reward_recipient, false));
+            }
+
+            remaining_accounts.push(AccountMeta::new_readonly(*compound, false));
+            if rules.min_holding_seconds > 0 {
+                remaining_accounts.push(AccountMeta::new_readonly(*holding, false));
+            }
+        }
+
+        let request = match &streams {
+            Some(pool) => program
+                .request()
+                .accounts(tax_token::accounts::DistributeStreamed {
+                    state,
+                    stats,
+                    stake_pool,
+                    eligibility,
+                    stream_pool,
+                    keeper: payer.pubkey(),
+                    reward_mint: *reward_token_mint,
+                    reward_vault,
+                    stream_vault: pool.vault,
+                    token_program: token_program_id,
+                    system_program: system_program_id,
+                })
+                .accounts(remaining_accounts)
+                .args(tax_token::instruction::DistributeStreamed { amounts }),
+            None => program
+                .request()
+                .accounts(tax_token::accounts::Distribute {
+                    state,
+                    stats,
+                    stake_pool,
+                    eligibility,
+                    keeper: payer.pubkey(),
+                    reward_mint: *reward_token_mint,
+                    reward_vault,
+                    token_program: token_program_id,
+                })
+                .accounts(remaining_accounts)
+                .args(tax_token::instruction::Distribute { amounts }),
+        };
+        let tx_hash = request.signer(payer).send().await?;
+        debug!("✅ Distributed batch with tx: {}", tx_hash);
     }
 
     info!(
         "✅ Distributed {} rewards to {} holders",
         total_rewards,
-        distribution_data.len()
+        holders.len()
     );
     Ok(())
 }
 
+/// Checkpoints a holder's holding record, which restarts their holding clock if they dropped
+/// below the minimum balance or weren't checkpointed recently
+///
+/// # Arguments
+/// * `program` - Tax program instance
+/// * `mint_account` - Token mint address
+/// * `owner` - Holder's wallet
+/// * `holder_token_account` - Holder's tax token ATA
+/// * `token_2022_program_id` - Token 2022 program ID
+/// * `system_program_id` - System program ID
+/// * `keypair` - Signer's keypair, which pays for new holding records
+async fn checkpoint_holding(
+    program: &Program<Arc<Keypair>>,
+    mint_account: &Pubkey,
+    owner: &Pubkey,
+    holder_token_account: &Pubkey,
+    token_2022_program_id: &Pubkey,
+    system_program_id: &Pubkey,
+    keypair: &Keypair,
+) -> Result<Signature, anyhow::Error> {
+    let (state, _) = Pubkey::find_program_address(&[b"program_state"], &program.id());
+    let (eligibility, _) = Pubkey::find_program_address(&[b"eligibility"], &program.id());
+    let (holding, _) = Pubkey::find_program_address(&[b"holding", owner.as_ref()], &program.id());
+    let tx_hash = program
+        .request()
+        .accounts(tax_token::accounts::CheckpointHolding {
+            state,
+            eligibility,
+            holding,
+            payer: keypair.pubkey(),
+            owner: *owner,
+            mint_account: *mint_account,
+            holder_token_account: *holder_token_account,
+            token_program: *token_2022_program_id,
+            system_program: *system_program_id,
+        })
+        .args(tax_token::instruction::CheckpointHolding {})
+        .signer(keypair)
+        .send()
+        .await?;
+
+    debug!("✅ Checkpointed {} with tx: {}", owner, tx_hash);
+    Ok(tx_hash)
+}
+
 /// Distributes tokens to holders (alternative distribution method)
 ///
 /// # Arguments
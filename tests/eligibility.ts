@@ -0,0 +1,98 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  program,
+  connection,
+  authority,
+  tokens,
+  outsider,
+  statePda,
+  eligibilityPda,
+  createTokenAccount,
+  createRewardAccount,
+  mintTokens,
+  fundRewards,
+  distribute,
+  expectError,
+  setup,
+} from "./setup";
+
+describe("eligibility", () => {
+  before(setup);
+
+  const holder = Keypair.generate();
+  const smallHolder = Keypair.generate();
+  let holderAccount: PublicKey;
+  let holderRewardAccount: PublicKey;
+  let smallHolderAccount: PublicKey;
+
+  const updateEligibility = (signer: Keypair, minBalance: anchor.BN) =>
+    program.methods
+      .updateEligibility(minBalance, new anchor.BN(0))
+      .accountsPartial({ state: statePda, eligibility: eligibilityPda, authority: signer.publicKey })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+
+  before(async () => {
+    holderAccount = await createTokenAccount(holder.publicKey);
+    holderRewardAccount = await createRewardAccount(holder.publicKey);
+    smallHolderAccount = await createTokenAccount(smallHolder.publicKey);
+    await createRewardAccount(smallHolder.publicKey);
+    await mintTokens(holderAccount, tokens(500));
+    await mintTokens(smallHolderAccount, tokens(10));
+    await fundRewards(tokens(10));
+
+    await program.methods
+      .initializeEligibility(tokens(100), new anchor.BN(0))
+      .accountsPartial({
+        state: statePda,
+        eligibility: eligibilityPda,
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("pays holders that meet the minimum balance", async () => {
+    await distribute([{ account: holderAccount, owner: holder.publicKey }], [tokens(1)]);
+
+    const account = await getAccount(connection, holderRewardAccount, "confirmed", TOKEN_PROGRAM_ID);
+    assert.equal(account.amount.toString(), tokens(1).toString());
+  });
+
+  it("rejects holders under the minimum balance", async () => {
+    await expectError(
+      distribute([{ account: smallHolderAccount, owner: smallHolder.publicKey }], [tokens(1)]),
+      "IneligibleRecipient"
+    );
+  });
+
+  it("rejects excluded owners until they are removed from the list", async () => {
+    const updateExcluded = (method: "addExcludedOwner" | "removeExcludedOwner") =>
+      program.methods[method](holder.publicKey)
+        .accountsPartial({ state: statePda, eligibility: eligibilityPda, authority: authority.publicKey })
+        .rpc({ commitment: "confirmed" });
+
+    await updateExcluded("addExcludedOwner");
+    const eligibility = await program.account.eligibility.fetch(eligibilityPda);
+    assert.ok(eligibility.excluded.some((owner) => owner.equals(holder.publicKey)));
+    await expectError(
+      distribute([{ account: holderAccount, owner: holder.publicKey }], [tokens(1)]),
+      "IneligibleRecipient"
+    );
+
+    await updateExcluded("removeExcludedOwner");
+    await distribute([{ account: holderAccount, owner: holder.publicKey }], [tokens(1)]);
+  });
+
+  it("applies a lowered minimum balance to the next distribution", async () => {
+    await updateEligibility(authority, tokens(10));
+    await distribute([{ account: smallHolderAccount, owner: smallHolder.publicKey }], [tokens(1)]);
+  });
+
+  it("only lets the authority change the rules", async () => {
+    await expectError(updateEligibility(outsider, new anchor.BN(0)), "UnauthorizedAccess");
+  });
+});
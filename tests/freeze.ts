@@ -0,0 +1,102 @@
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  program,
+  connection,
+  authority,
+  tokens,
+  tokenMint,
+  outsider,
+  pda,
+  statePda,
+  createTokenAccount,
+  mintTokens,
+  expectError,
+  setup,
+} from "./setup";
+
+describe("freeze records", () => {
+  before(setup);
+
+  const holder = Keypair.generate();
+  let holderAccount: PublicKey;
+  let freezeRecord: PublicKey;
+
+  const freezeAction = (index: number) => {
+    const seed = Buffer.alloc(4);
+    seed.writeUInt32LE(index);
+    return pda(Buffer.from("freeze_action"), holderAccount.toBuffer(), seed);
+  };
+
+  const updateFreeze = async (method: "freezeHolder" | "thawHolder", signer: Keypair, reasonCode: number) => {
+    const record = await program.account.freezeRecord.fetchNullable(freezeRecord);
+    return program.methods[method](reasonCode)
+      .accountsPartial({
+        state: statePda,
+        freezeRecord,
+        freezeAction: freezeAction(record?.actionCount ?? 0),
+        officer: signer.publicKey,
+        mintAccount: tokenMint,
+        tokenAccount: holderAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+  };
+
+  const isFrozen = async () =>
+    (await getAccount(connection, holderAccount, "confirmed", TOKEN_2022_PROGRAM_ID)).isFrozen;
+
+  before(async () => {
+    holderAccount = await createTokenAccount(holder.publicKey);
+    freezeRecord = pda(Buffer.from("freeze"), holderAccount.toBuffer());
+    await mintTokens(holderAccount, tokens(10));
+  });
+
+  it("requires a reason code", async () => {
+    await expectError(updateFreeze("freezeHolder", authority, 0), "MissingReasonCode");
+  });
+
+  it("only lets the compliance role freeze accounts", async () => {
+    await expectError(updateFreeze("freezeHolder", outsider, 1), "UnauthorizedAccess");
+  });
+
+  it("freezes the account and records the action", async () => {
+    await updateFreeze("freezeHolder", authority, 7);
+    assert.ok(await isFrozen());
+
+    const record = await program.account.freezeRecord.fetch(freezeRecord);
+    assert.ok(record.tokenAccount.equals(holderAccount));
+    assert.ok(record.owner.equals(holder.publicKey));
+    assert.ok(record.officer.equals(authority.publicKey));
+    assert.ok(record.frozen);
+    assert.equal(record.reasonCode, 7);
+    assert.equal(record.freezeCount, 1);
+    assert.equal(record.actionCount, 1);
+
+    const action = await program.account.freezeAction.fetch(freezeAction(0));
+    assert.equal(action.index, 0);
+    assert.ok(action.frozen);
+    assert.equal(action.reasonCode, 7);
+  });
+
+  it("thaws the account and keeps every earlier action", async () => {
+    await updateFreeze("thawHolder", authority, 8);
+    assert.ok(!(await isFrozen()));
+
+    const record = await program.account.freezeRecord.fetch(freezeRecord);
+    assert.ok(!record.frozen);
+    assert.equal(record.reasonCode, 8);
+    assert.equal(record.freezeCount, 1);
+    assert.equal(record.actionCount, 2);
+
+    const freeze = await program.account.freezeAction.fetch(freezeAction(0));
+    const thaw = await program.account.freezeAction.fetch(freezeAction(1));
+    assert.ok(freeze.frozen);
+    assert.equal(thaw.index, 1);
+    assert.ok(!thaw.frozen);
+    assert.equal(thaw.reasonCode, 8);
+  });
+});
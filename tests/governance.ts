@@ -0,0 +1,139 @@
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getMint,
+  getTransferFeeConfig,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  program,
+  connection,
+  authority,
+  tokens,
+  tokenMint,
+  pda,
+  statePda,
+  stakePoolPda,
+  streamPoolPda,
+  governancePda,
+  charitiesPda,
+  ata,
+  rewardMint,
+  rewardVault,
+  stakePositionPda,
+  stake,
+  expectError,
+  setupStaking,
+} from "./setup";
+
+describe("governance", () => {
+  before(setupStaking);
+
+  const governanceVault = ata(governancePda);
+  const proposalPda = pda(Buffer.from("proposal"), new anchor.BN(0).toArrayLike(Buffer, "le", 8));
+  const positionPda = stakePositionPda(authority.publicKey, 0);
+  const votingPeriod = 3;
+  let proposerAccount: PublicKey;
+
+  const execute = () =>
+    program.methods
+      .executeProposal()
+      .accountsPartial({
+        state: statePda,
+        governance: governancePda,
+        proposal: proposalPda,
+        stakePool: stakePoolPda,
+        streamPool: streamPoolPda,
+        streamVault: streamPoolPda,
+        rewardMint,
+        rewardVault,
+        charities: charitiesPda,
+        mintAccount: tokenMint,
+        vault: governanceVault,
+        proposerTokenAccount: proposerAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+  before(async () => {
+    proposerAccount = ata(authority.publicKey);
+
+    await stake(authority, { days30: {} }, positionPda, proposerAccount, tokens(1_000));
+
+    await program.methods
+      .initializeGovernance({
+        proposalDeposit: tokens(10),
+        votingPeriod: new anchor.BN(votingPeriod),
+        quorum: tokens(100),
+      })
+      .accountsPartial({
+        state: statePda,
+        governance: governancePda,
+        authority: authority.publicKey,
+        mintAccount: tokenMint,
+        vault: governanceVault,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .createProposal({
+        updateFee: { transferFeeBasisPoints: 250, maximumFee: tokens(50) },
+      })
+      .accountsPartial({
+        state: statePda,
+        governance: governancePda,
+        proposal: proposalPda,
+        proposer: authority.publicKey,
+        mintAccount: tokenMint,
+        proposerTokenAccount: proposerAccount,
+        vault: governanceVault,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("weights votes by the voter's stake positions", async () => {
+    await program.methods
+      .castVote(true)
+      .accountsPartial({
+        proposal: proposalPda,
+        vote: pda(Buffer.from("vote"), proposalPda.toBuffer(), authority.publicKey.toBuffer()),
+        voter: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: positionPda, isSigner: false, isWritable: false }])
+      .rpc({ commitment: "confirmed" });
+
+    const position = await program.account.stakePosition.fetch(positionPda);
+    const proposal = await program.account.proposal.fetch(proposalPda);
+    assert.ok(proposal.yesVotes.eq(position.amount));
+    assert.ok(proposal.noVotes.isZero());
+  });
+
+  it("rejects execution while voting is open", async () => {
+    await expectError(execute(), "VotingNotEnded");
+  });
+
+  it("applies a passed proposal's action once voting has ended", async () => {
+    await new Promise((resolve) => setTimeout(resolve, (votingPeriod + 2) * 1000));
+    await execute();
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+    assert.deepEqual(proposal.status, { executed: {} });
+
+    const mint = await getMint(connection, tokenMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+    const feeConfig = getTransferFeeConfig(mint);
+    assert.equal(feeConfig.newerTransferFee.transferFeeBasisPoints, 250);
+    assert.equal(feeConfig.newerTransferFee.maximumFee.toString(), tokens(50).toString());
+  });
+
+  it("can't be executed twice", async () => {
+    await expectError(execute(), "ProposalFinalized");
+  });
+});
@@ -0,0 +1,75 @@
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+import { program, authority, tokens, tokenMint, outsider, statePda, expectError, setup } from "./setup";
+
+describe("role gates", () => {
+  before(setup);
+
+  const updateFee = (signer: Keypair, basisPoints: number) =>
+    program.methods
+      .updateFee(basisPoints, tokens(1_000))
+      .accountsPartial({
+        state: statePda,
+        authority: signer.publicKey,
+        mintAccount: tokenMint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+
+  const grantRole = (signer: Keypair, role: any, account: PublicKey) =>
+    program.methods
+      .grantRole(role, account)
+      .accountsPartial({ state: statePda, authority: signer.publicKey })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+
+  it("rejects fee updates from keys without the fee admin role", async () => {
+    await expectError(updateFee(outsider, 500), "UnauthorizedAccess");
+  });
+
+  it("moves the fee admin role to the granted key", async () => {
+    await grantRole(authority, { feeAdmin: {} }, outsider.publicKey);
+    await updateFee(outsider, 900);
+    await expectError(updateFee(authority, 900), "UnauthorizedAccess");
+
+    await grantRole(authority, { feeAdmin: {} }, authority.publicKey);
+    await expectError(updateFee(outsider, 900), "UnauthorizedAccess");
+  });
+
+  it("leaves a revoked role without a holder", async () => {
+    await program.methods
+      .revokeRole({ pauser: {} })
+      .accountsPartial({ state: statePda, authority: authority.publicKey })
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.programState.fetch(statePda);
+    assert.ok(state.pauser.equals(PublicKey.default));
+
+    await grantRole(authority, { pauser: {} }, authority.publicKey);
+  });
+
+  it("only lets the authority grant roles", async () => {
+    await expectError(
+      grantRole(outsider, { treasuryAdmin: {} }, outsider.publicKey),
+      "UnauthorizedAccess"
+    );
+  });
+
+  it("rejects pausing from keys without the pauser role", async () => {
+    await expectError(
+      program.methods
+        .pause()
+        .accountsPartial({
+          state: statePda,
+          pauser: outsider.publicKey,
+          mintAccount: tokenMint,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([outsider])
+        .rpc({ commitment: "confirmed" }),
+      "UnauthorizedAccess"
+    );
+  });
+});
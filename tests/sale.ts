@@ -0,0 +1,91 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  program,
+  connection,
+  authority,
+  tokens,
+  tokenMint,
+  pda,
+  statePda,
+  salePda,
+  ata,
+  airdrop,
+  chainTime,
+  expectError,
+  setup,
+} from "./setup";
+
+describe("sale", () => {
+  before(setup);
+
+  const buyers = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+
+  const buy = (buyer: Keypair, amount: anchor.BN) =>
+    program.methods
+      .buy(amount)
+      .accountsPartial({
+        state: statePda,
+        sale: salePda,
+        purchase: pda(Buffer.from("purchase"), buyer.publicKey.toBuffer()),
+        buyer: buyer.publicKey,
+        mintAccount: tokenMint,
+        buyerTokenAccount: ata(buyer.publicKey),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buyer])
+      .rpc({ commitment: "confirmed" });
+
+  before(async () => {
+    for (const buyer of buyers) {
+      await airdrop(buyer.publicKey, 10);
+    }
+
+    const now = await chainTime();
+    await program.methods
+      .initializeSale({
+        priceLamports: new anchor.BN(LAMPORTS_PER_SOL / 100),
+        startTs: new anchor.BN(now - 60),
+        endTs: new anchor.BN(now + 3_600),
+        hardCap: tokens(100),
+        walletCap: tokens(40),
+      })
+      .accountsPartial({
+        state: statePda,
+        sale: salePda,
+        authority: authority.publicKey,
+        mintAccount: tokenMint,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("mints purchased tokens and charges the price", async () => {
+    const lamportsBefore = await connection.getBalance(salePda, "confirmed");
+    await buy(buyers[0], tokens(40));
+
+    const account = await getAccount(connection, ata(buyers[0].publicKey), "confirmed", TOKEN_2022_PROGRAM_ID);
+    assert.equal(account.amount.toString(), tokens(40).toString());
+
+    const lamportsAfter = await connection.getBalance(salePda, "confirmed");
+    assert.equal(lamportsAfter - lamportsBefore, (40 * LAMPORTS_PER_SOL) / 100);
+  });
+
+  it("rejects purchases over the wallet cap", async () => {
+    await expectError(buy(buyers[0], new anchor.BN(1)), "WalletCapExceeded");
+    await expectError(buy(buyers[1], tokens(41)), "WalletCapExceeded");
+  });
+
+  it("rejects purchases over the hard cap", async () => {
+    await buy(buyers[1], tokens(40));
+    await expectError(buy(buyers[2], tokens(21)), "SaleHardCapExceeded");
+    await buy(buyers[2], tokens(20));
+
+    const sale = await program.account.sale.fetch(salePda);
+    assert.ok(sale.sold.eq(sale.hardCap));
+  });
+});
@@ -0,0 +1,277 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TaxToken } from "../target/types/tax_token";
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+// Shared fixture for the behavioural tests, run against a fresh local validator with
+// `anchor test --provider.cluster localnet`
+// The program state and the other singleton PDAs can only be initialized once per validator,
+// so every test file awaits `setup()`, which initializes the program on first use
+export const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+
+export const program = anchor.workspace.TaxToken as Program<TaxToken>;
+export const connection = provider.connection;
+export const authority = (provider.wallet as anchor.Wallet).payer;
+
+export const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+
+export const decimals = 9;
+const unit = new anchor.BN(10).pow(new anchor.BN(decimals));
+export const tokens = (amount: number) => new anchor.BN(amount).mul(unit);
+
+const tokenMintKeypair = Keypair.generate();
+export const tokenMint = tokenMintKeypair.publicKey;
+export const outsider = Keypair.generate();
+export const recipient = Keypair.generate();
+
+export const pda = (...seeds: Buffer[]) =>
+  PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+export const statePda = pda(Buffer.from("program_state"));
+export const statsPda = pda(Buffer.from("stats"));
+export const treasuryPda = pda(Buffer.from("treasury"));
+export const stakePoolPda = pda(Buffer.from("stake_pool"));
+export const streamPoolPda = pda(Buffer.from("stream_pool"));
+export const governancePda = pda(Buffer.from("governance"));
+export const charitiesPda = pda(Buffer.from("charities"));
+export const salePda = pda(Buffer.from("sale"));
+export const eligibilityPda = pda(Buffer.from("eligibility"));
+
+// the position PDA of a stake term, numbered in `StakeTerm` order
+export const stakePositionPda = (owner: PublicKey, term: number) =>
+  pda(Buffer.from("stake"), owner.toBuffer(), Buffer.from([term]));
+
+export const ata = (owner: PublicKey) =>
+  getAssociatedTokenAddressSync(tokenMint, owner, true, TOKEN_2022_PROGRAM_ID);
+
+export const stakeVault = ata(stakePoolPda);
+
+export let rewardMint: PublicKey;
+export let rewardVault: PublicKey;
+
+export async function airdrop(to: PublicKey, sol: number) {
+  const signature = await connection.requestAirdrop(to, sol * LAMPORTS_PER_SOL);
+  const latest = await connection.getLatestBlockhash();
+  await connection.confirmTransaction({ signature, ...latest }, "confirmed");
+}
+
+export async function createTokenAccount(owner: PublicKey): Promise<PublicKey> {
+  const account = await getOrCreateAssociatedTokenAccount(
+    connection,
+    authority,
+    tokenMint,
+    owner,
+    true,
+    "confirmed",
+    undefined,
+    TOKEN_2022_PROGRAM_ID,
+    ASSOCIATED_TOKEN_PROGRAM_ID
+  );
+  return account.address;
+}
+
+// the owner's associated token account of the reward mint
+export async function createRewardAccount(owner: PublicKey): Promise<PublicKey> {
+  const account = await getOrCreateAssociatedTokenAccount(
+    connection,
+    authority,
+    rewardMint,
+    owner,
+    true,
+    "confirmed",
+    undefined,
+    TOKEN_PROGRAM_ID,
+    ASSOCIATED_TOKEN_PROGRAM_ID
+  );
+  return account.address;
+}
+
+// mints reward tokens straight into the reward vault
+export async function fundRewards(amount: anchor.BN) {
+  await mintTo(
+    connection,
+    authority,
+    rewardMint,
+    rewardVault,
+    authority,
+    amount.toNumber(),
+    [],
+    { commitment: "confirmed" },
+    TOKEN_PROGRAM_ID
+  );
+}
+
+export async function mintTokens(tokenAccount: PublicKey, amount: anchor.BN) {
+  await program.methods
+    .mintTo(amount)
+    .accountsPartial({
+      state: statePda,
+      authority: authority.publicKey,
+      mintAccount: tokenMint,
+      tokenAccount,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function chainTime(): Promise<number> {
+  const slot = await connection.getSlot("confirmed");
+  return (await connection.getBlockTime(slot)) ?? Math.floor(Date.now() / 1000);
+}
+
+export const stake = (
+  owner: Keypair,
+  term: object,
+  position: PublicKey,
+  ownerTokenAccount: PublicKey,
+  amount: anchor.BN
+) =>
+  program.methods
+    .stake(term as any, amount)
+    .accountsPartial({
+      state: statePda,
+      stakePool: stakePoolPda,
+      position,
+      owner: owner.publicKey,
+      mintAccount: tokenMint,
+      ownerTokenAccount,
+      vault: stakeVault,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+// pays `amounts` of the reward mint through `distribute`, one per holder token account, each to
+// the holder's associated reward token account
+export const distribute = (
+  holders: { account: PublicKey; owner: PublicKey }[],
+  amounts: anchor.BN[],
+  signer: Keypair = authority
+) =>
+  program.methods
+    .distribute(amounts)
+    .accountsPartial({
+      state: statePda,
+      stats: statsPda,
+      stakePool: stakePoolPda,
+      eligibility: eligibilityPda,
+      keeper: signer.publicKey,
+      rewardMint,
+      rewardVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(
+      holders.flatMap(({ account, owner }) =>
+        [
+          account,
+          getAssociatedTokenAddressSync(rewardMint, owner, true, TOKEN_PROGRAM_ID),
+          pda(Buffer.from("reward_recipient"), owner.toBuffer()),
+          pda(Buffer.from("compound"), owner.toBuffer()),
+        ].map((pubkey, index) => ({ pubkey, isSigner: false, isWritable: index == 1 }))
+      )
+    )
+    .signers([signer])
+    .rpc({ commitment: "confirmed" });
+
+// asserts that the call fails with the given program error
+export async function expectError(call: Promise<unknown>, code: string) {
+  try {
+    await call;
+  } catch (err) {
+    const error = anchor.AnchorError.parse(err.logs) ?? err;
+    assert.equal(error.error?.errorCode?.code, code, err.toString());
+    return;
+  }
+  assert.fail(`expected ${code}`);
+}
+
+let initialized: Promise<void> | undefined;
+let stakingInitialized: Promise<void> | undefined;
+
+// initializes the program once per validator, whichever test file runs first
+export function setup(): Promise<void> {
+  initialized ??= initialize();
+  return initialized;
+}
+
+// initializes the stake pool once, for the test files that stake
+export function setupStaking(): Promise<void> {
+  stakingInitialized ??= setup().then(initializeStaking);
+  return stakingInitialized;
+}
+
+async function initialize() {
+  await airdrop(outsider.publicKey, 10);
+  await airdrop(recipient.publicKey, 10);
+
+  rewardMint = await createMint(
+    connection,
+    authority,
+    authority.publicKey,
+    null,
+    decimals,
+    undefined,
+    { commitment: "confirmed" },
+    TOKEN_PROGRAM_ID
+  );
+  rewardVault = await createRewardAccount(statePda);
+
+  await program.methods
+    .initialize({
+      name: "Tax Token",
+      symbol: "TAX",
+      uri: "https://example.com/tax.json",
+      decimals,
+      totalSupply: tokens(1_000_000),
+      defaultAccountState: false,
+      confidentialTransfer: null,
+      groupPointer: false,
+      groupMemberPointer: false,
+    })
+    .accountsPartial({
+      state: statePda,
+      stats: statsPda,
+      tokenMint,
+      authority: authority.publicKey,
+      rewardMint,
+      metadata: tokenMint,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    })
+    .signers([tokenMintKeypair])
+    .rpc({ commitment: "confirmed" });
+
+  await mintTokens(await createTokenAccount(authority.publicKey), tokens(10_000));
+}
+
+async function initializeStaking() {
+  await program.methods
+    .initializeStaking()
+    .accountsPartial({
+      state: statePda,
+      stakePool: stakePoolPda,
+      authority: authority.publicKey,
+      mintAccount: tokenMint,
+      vault: stakeVault,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc({ commitment: "confirmed" });
+}
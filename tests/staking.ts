@@ -0,0 +1,149 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  program,
+  connection,
+  authority,
+  tokens,
+  tokenMint,
+  outsider,
+  statePda,
+  statsPda,
+  stakePoolPda,
+  stakePositionPda,
+  stakeVault,
+  rewardMint,
+  rewardVault,
+  airdrop,
+  createTokenAccount,
+  createRewardAccount,
+  mintTokens,
+  fundRewards,
+  stake,
+  distribute,
+  expectError,
+  setupStaking,
+} from "./setup";
+
+describe("staking", () => {
+  before(setupStaking);
+
+  const REWARD_PRECISION = new anchor.BN(10).pow(new anchor.BN(12));
+  const staker = Keypair.generate();
+  const position = stakePositionPda(staker.publicKey, 1);
+  let stakerAccount: PublicKey;
+  let stakerRewardAccount: PublicKey;
+
+  const distributeToStakers = (signer: Keypair, amount: anchor.BN) =>
+    program.methods
+      .distributeToStakers(amount)
+      .accountsPartial({
+        state: statePda,
+        stats: statsPda,
+        stakePool: stakePoolPda,
+        keeper: signer.publicKey,
+        rewardMint,
+        rewardVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+
+  before(async () => {
+    await airdrop(staker.publicKey, 10);
+    stakerAccount = await createTokenAccount(staker.publicKey);
+    stakerRewardAccount = await createRewardAccount(staker.publicKey);
+    await mintTokens(stakerAccount, tokens(2_000));
+  });
+
+  it("weights a position by its term's multiplier", async () => {
+    const poolBefore = await program.account.stakePool.fetch(stakePoolPda);
+    await stake(staker, { days90: {} }, position, stakerAccount, tokens(1_000));
+
+    // the transfer fee is withheld from the staked amount
+    const staked = await program.account.stakePosition.fetch(position);
+    assert.ok(staked.amount.gtn(0));
+    assert.ok(staked.amount.lt(tokens(1_000)));
+    assert.ok(staked.weight.eq(staked.amount.muln(3).divn(2)));
+
+    const pool = await program.account.stakePool.fetch(stakePoolPda);
+    assert.ok(pool.totalStaked.sub(poolBefore.totalStaked).eq(staked.amount));
+    assert.ok(pool.totalWeight.sub(poolBefore.totalWeight).eq(staked.weight));
+  });
+
+  it("rejects unstaking before the term ends", async () => {
+    await expectError(
+      program.methods
+        .unstake({ days90: {} })
+        .accountsPartial({
+          state: statePda,
+          stakePool: stakePoolPda,
+          position,
+          owner: staker.publicKey,
+          mintAccount: tokenMint,
+          ownerTokenAccount: stakerAccount,
+          vault: stakeVault,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([staker])
+        .rpc({ commitment: "confirmed" }),
+      "StakeLocked"
+    );
+  });
+
+  it("pays stakers their share of the staker rewards by weight", async () => {
+    await fundRewards(tokens(100));
+    const poolBefore = await program.account.stakePool.fetch(stakePoolPda);
+    await distributeToStakers(authority, tokens(100));
+
+    const pool = await program.account.stakePool.fetch(stakePoolPda);
+    assert.ok(pool.unclaimedRewards.sub(poolBefore.unclaimedRewards).eq(tokens(100)));
+
+    const staked = await program.account.stakePosition.fetch(position);
+    const earned = staked.weight.mul(pool.accRewardPerWeight).div(REWARD_PRECISION).sub(staked.rewardDebt);
+    assert.ok(earned.gtn(0));
+
+    await program.methods
+      .claimStakeRewards({ days90: {} })
+      .accountsPartial({
+        state: statePda,
+        stakePool: stakePoolPda,
+        position,
+        owner: staker.publicKey,
+        rewardMint,
+        rewardVault,
+        ownerRewardAccount: stakerRewardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([staker])
+      .rpc({ commitment: "confirmed" });
+
+    const account = await getAccount(connection, stakerRewardAccount, "confirmed", TOKEN_PROGRAM_ID);
+    assert.equal(account.amount.toString(), earned.toString());
+
+    const poolAfter = await program.account.stakePool.fetch(stakePoolPda);
+    assert.ok(pool.unclaimedRewards.sub(poolAfter.unclaimedRewards).eq(earned));
+  });
+
+  it("keeps unclaimed staker rewards out of later distributions", async () => {
+    const pool = await program.account.stakePool.fetch(stakePoolPda);
+    const vault = await getAccount(connection, rewardVault, "confirmed", TOKEN_PROGRAM_ID);
+    const available = new anchor.BN(vault.amount.toString()).sub(pool.unclaimedRewards);
+    await expectError(distributeToStakers(authority, available.addn(1)), "InsufficientRewards");
+  });
+
+  it("only lets the keeper fund staker rewards", async () => {
+    await expectError(distributeToStakers(outsider, new anchor.BN(1)), "UnauthorizedAccess");
+  });
+
+  it("excludes the stake vault from holder distributions", async () => {
+    await fundRewards(new anchor.BN(1));
+    await expectError(
+      distribute([{ account: stakeVault, owner: stakePoolPda }], [new anchor.BN(1)]),
+      "IneligibleRecipient"
+    );
+  });
+});
@@ -0,0 +1,100 @@
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  program,
+  connection,
+  authority,
+  tokens,
+  tokenMint,
+  outsider,
+  recipient,
+  statePda,
+  treasuryPda,
+  ata,
+  createTokenAccount,
+  mintTokens,
+  expectError,
+  setup,
+} from "./setup";
+
+describe("treasury", () => {
+  before(setup);
+
+  const vault = ata(treasuryPda);
+  let recipientAccount: PublicKey;
+  let outsiderAccount: PublicKey;
+
+  const spend = (signer: Keypair, destination: PublicKey, amount: anchor.BN) =>
+    program.methods
+      .treasurySpend(amount)
+      .accountsPartial({
+        state: statePda,
+        treasury: treasuryPda,
+        authority: signer.publicKey,
+        mintAccount: tokenMint,
+        vault,
+        destination,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+
+  before(async () => {
+    await program.methods
+      .initializeTreasury(tokens(1_000), new anchor.BN(30 * 24 * 3_600))
+      .accountsPartial({
+        state: statePda,
+        treasury: treasuryPda,
+        authority: authority.publicKey,
+        mintAccount: tokenMint,
+        vault,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .addTreasuryDestination(recipient.publicKey)
+      .accountsPartial({
+        state: statePda,
+        treasury: treasuryPda,
+        authority: authority.publicKey,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    await mintTokens(vault, tokens(5_000));
+    recipientAccount = await createTokenAccount(recipient.publicKey);
+    outsiderAccount = await createTokenAccount(outsider.publicKey);
+  });
+
+  it("spends to an allowlisted destination within the allowance", async () => {
+    await spend(authority, recipientAccount, tokens(600));
+
+    const treasury = await program.account.treasury.fetch(treasuryPda);
+    assert.ok(treasury.allowance.eq(tokens(400)));
+
+    // the transfer fee is withheld from the amount received
+    const account = await getAccount(connection, recipientAccount, "confirmed", TOKEN_2022_PROGRAM_ID);
+    const received = new anchor.BN(account.amount.toString());
+    assert.ok(received.gtn(0));
+    assert.ok(received.lt(tokens(600)));
+  });
+
+  // the allowance refills by 1,000 tokens a month, so only a fraction of a token comes back during the test
+  it("rejects spends past the allowance", async () => {
+    await expectError(spend(authority, recipientAccount, tokens(500)), "SpendLimitExceeded");
+    await spend(authority, recipientAccount, tokens(400));
+    await expectError(spend(authority, recipientAccount, tokens(1)), "SpendLimitExceeded");
+  });
+
+  it("rejects destinations that are not allowlisted", async () => {
+    await expectError(spend(authority, outsiderAccount, new anchor.BN(1)), "DestinationNotAllowed");
+  });
+
+  it("rejects spends from keys without the treasury admin role", async () => {
+    await expectError(spend(outsider, recipientAccount, new anchor.BN(1)), "UnauthorizedAccess");
+  });
+});
@@ -0,0 +1,150 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  program,
+  connection,
+  authority,
+  tokens,
+  tokenMint,
+  outsider,
+  pda,
+  statePda,
+  ata,
+  airdrop,
+  chainTime,
+  createTokenAccount,
+  expectError,
+  setup,
+} from "./setup";
+
+describe("vesting", () => {
+  before(setup);
+
+  const beneficiary = Keypair.generate();
+  let beneficiaryAccount: PublicKey;
+
+  const vestingPda = (scheduleId: number) =>
+    pda(
+      Buffer.from("vesting"),
+      beneficiary.publicKey.toBuffer(),
+      new anchor.BN(scheduleId).toArrayLike(Buffer, "le", 8)
+    );
+
+  const balance = async (account: PublicKey) =>
+    new anchor.BN((await getAccount(connection, account, "confirmed", TOKEN_2022_PROGRAM_ID)).amount.toString());
+
+  const createVesting = async (
+    signer: Keypair,
+    scheduleId: number,
+    amount: anchor.BN,
+    offsets: { start: number; cliff: number; end: number },
+    revocable: boolean
+  ) => {
+    const now = await chainTime();
+    const vesting = vestingPda(scheduleId);
+    return program.methods
+      .createVesting({
+        beneficiary: beneficiary.publicKey,
+        scheduleId: new anchor.BN(scheduleId),
+        amount,
+        startTs: new anchor.BN(now + offsets.start),
+        cliffTs: new anchor.BN(now + offsets.cliff),
+        endTs: new anchor.BN(now + offsets.end),
+        revocable,
+      })
+      .accountsPartial({
+        state: statePda,
+        vesting,
+        authority: signer.publicKey,
+        mintAccount: tokenMint,
+        authorityTokenAccount: ata(signer.publicKey),
+        vault: ata(vesting),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+  };
+
+  const release = (scheduleId: number) =>
+    program.methods
+      .releaseVested()
+      .accountsPartial({
+        state: statePda,
+        vesting: vestingPda(scheduleId),
+        beneficiary: beneficiary.publicKey,
+        mintAccount: tokenMint,
+        vault: ata(vestingPda(scheduleId)),
+        beneficiaryTokenAccount: beneficiaryAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([beneficiary])
+      .rpc({ commitment: "confirmed" });
+
+  const revoke = (scheduleId: number) =>
+    program.methods
+      .revokeVesting()
+      .accountsPartial({
+        state: statePda,
+        vesting: vestingPda(scheduleId),
+        authority: authority.publicKey,
+        mintAccount: tokenMint,
+        vault: ata(vestingPda(scheduleId)),
+        authorityTokenAccount: ata(authority.publicKey),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+  before(async () => {
+    await airdrop(beneficiary.publicKey, 10);
+    beneficiaryAccount = await createTokenAccount(beneficiary.publicKey);
+    await createTokenAccount(outsider.publicKey);
+  });
+
+  it("delivers the full amount net of the transfer fee once vested", async () => {
+    await createVesting(authority, 0, tokens(100), { start: -200, cliff: -100, end: -10 }, false);
+
+    // the vault holds more than the amount, to cover the fee on the releases
+    assert.ok((await balance(ata(vestingPda(0)))).gt(tokens(100)));
+
+    await release(0);
+    assert.ok((await balance(beneficiaryAccount)).eq(tokens(100)));
+
+    const vesting = await program.account.vesting.fetch(vestingPda(0));
+    assert.ok(vesting.released.eq(tokens(100)));
+
+    await expectError(release(0), "NothingToRelease");
+  });
+
+  it("releases nothing before the cliff", async () => {
+    await createVesting(authority, 1, tokens(100), { start: 0, cliff: 3_600, end: 7_200 }, true);
+    await expectError(release(1), "NothingToRelease");
+  });
+
+  it("returns the unvested tokens to the authority when revoked", async () => {
+    const authorityBefore = await balance(ata(authority.publicKey));
+    await revoke(1);
+
+    const vesting = await program.account.vesting.fetch(vestingPda(1));
+    assert.ok(vesting.revoked);
+    assert.ok(vesting.totalAmount.isZero());
+    assert.ok((await balance(ata(vestingPda(1)))).isZero());
+    assert.ok((await balance(ata(authority.publicKey))).gt(authorityBefore));
+
+    await expectError(revoke(1), "VestingNotRevocable");
+  });
+
+  it("rejects revoking a schedule created as irrevocable", async () => {
+    await expectError(revoke(0), "VestingNotRevocable");
+  });
+
+  it("only lets the authority create schedules", async () => {
+    await expectError(
+      createVesting(outsider, 2, tokens(1), { start: 0, cliff: 0, end: 60 }, false),
+      "UnauthorizedAccess"
+    );
+  });
+});